@@ -0,0 +1,141 @@
+/// Where the sequence `x, f(x), f(f(x)), ...` starts repeating and how long the repetition is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The index of the first element that is part of the cycle.
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Finds the cycle of the sequence starting at `initial` using Brent's algorithm, which only
+/// keeps two states around. The sequence has to end up in a cycle eventually, e.g. because there
+/// are only finitely many states.
+pub fn brent<S: Clone + PartialEq>(initial: S, f: impl Fn(&S) -> S) -> Cycle {
+    // find the cycle length by moving the hare ahead in powers of two
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = f(&initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = f(&hare);
+        len += 1;
+    }
+
+    // with the hare `len` ahead, both meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..len {
+        hare = f(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    a / gcd(a, b) * b
+}
+
+/// Solves the system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder
+/// theorem, generalized to moduli that aren't coprime. Returns the smallest non-negative solution
+/// and the least common multiple of the moduli, as all solutions are that far apart, or `None`
+/// if the congruences contradict each other.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(x, modulus), (residue, other_modulus)| {
+            let residue = residue.rem_euclid(other_modulus);
+            // x + k * modulus ≡ residue (mod other_modulus), solved for k
+            let (g, inverse, _) = extended_gcd(modulus, other_modulus);
+            let difference = residue - x;
+            if difference % g != 0 {
+                return None;
+            }
+
+            let reduced_modulus = other_modulus / g;
+            let k = (difference / g % reduced_modulus * inverse).rem_euclid(reduced_modulus);
+            let lcm = modulus * reduced_modulus;
+            Some(((x + k * modulus).rem_euclid(lcm), lcm))
+        })
+}
+
+/// `(gcd(a, b), x, y)` with `a * x + b * y = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_brent() {
+        // 0, 1, 2, 3, 4, 2, 3, 4, ...
+        let cycle = brent(0, |&x| if x == 4 { 2 } else { x + 1 });
+        assert_eq!(cycle, Cycle { start: 2, len: 3 });
+
+        let cycle = brent(7, |&x| x);
+        assert_eq!(cycle, Cycle { start: 0, len: 1 });
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(7, 1), 7);
+        assert_eq!(gcd(0, 5), 5);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // not coprime, but consistent
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+    }
+
+    proptest! {
+        #[test]
+        fn test_crt_solves_congruences(x in 0i128..10_000, moduli in prop::collection::vec(1i128..60, 1..5)) {
+            let (solution, lcm) = crt(moduli.iter().map(|&modulus| (x % modulus, modulus)))
+                .expect("x solves the congruences");
+
+            prop_assert!(solution < lcm);
+            prop_assert_eq!(solution, x % lcm);
+        }
+
+        #[test]
+        fn test_brent_finds_cycle(start in 0usize..20, len in 1usize..20) {
+            let f = |&x: &usize| if x + 1 == start + len { start } else { x + 1 };
+
+            prop_assert_eq!(brent(0, f), Cycle { start, len });
+        }
+    }
+}
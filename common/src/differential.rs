@@ -0,0 +1,249 @@
+use std::env;
+use std::fmt::{self, Display};
+
+use eyre::{bail, WrapErr};
+
+use crate::minimize::{catch_panic, ddmin, InputPart};
+use crate::normalize::normalize;
+use crate::PuzzlePart;
+
+/// Every registered implementation of a day's puzzle parts, so that simple implementations can
+/// serve as oracles for optimized ones.
+pub struct Solutions<T> {
+    implementations: Vec<Implementation<T>>,
+}
+
+struct Implementation<T> {
+    puzzle_part: PuzzlePart,
+    name: &'static str,
+    solve: fn(&str) -> eyre::Result<T>,
+}
+
+/// What running an implementation on an input resulted in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Answer(T),
+    Error(String),
+    Panic,
+}
+
+/// Two or more implementations of the same puzzle part that don't agree on an input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disagreement<T> {
+    pub puzzle_part: PuzzlePart,
+    pub input: String,
+    pub outcomes: Vec<(&'static str, Outcome<T>)>,
+}
+
+impl<T> Default for Solutions<T> {
+    fn default() -> Self {
+        Self {
+            implementations: vec![],
+        }
+    }
+}
+
+impl<T: PartialEq + Display> Solutions<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        mut self,
+        puzzle_part: PuzzlePart,
+        name: &'static str,
+        solve: fn(&str) -> eyre::Result<T>,
+    ) -> Self {
+        self.implementations.push(Implementation {
+            puzzle_part,
+            name,
+            solve,
+        });
+        self
+    }
+
+    pub fn names(&self, puzzle_part: PuzzlePart) -> impl Iterator<Item = &'static str> + '_ {
+        self.implementations_of(puzzle_part)
+            .map(|implementation| implementation.name)
+    }
+
+    /// Runs every implementation of `puzzle_part` on `input`.
+    pub fn run(&self, puzzle_part: PuzzlePart, input: &str) -> Vec<(&'static str, Outcome<T>)> {
+        self.implementations_of(puzzle_part)
+            .map(|implementation| {
+                let _span =
+                    tracing::info_span!("implementation", name = implementation.name).entered();
+                let outcome = match catch_panic(|| (implementation.solve)(input)) {
+                    Ok(Ok(answer)) => Outcome::Answer(answer),
+                    Ok(Err(err)) => Outcome::Error(format!("{err:#}")),
+                    Err(_) => Outcome::Panic,
+                };
+
+                (implementation.name, outcome)
+            })
+            .collect()
+    }
+
+    /// Whether all implementations of `puzzle_part` agree on `input`. Errors and panics agree with
+    /// each other, but never with an answer.
+    pub fn agree(&self, puzzle_part: PuzzlePart, input: &str) -> bool {
+        let outcomes = self.run(puzzle_part, input);
+        outcomes
+            .windows(2)
+            .all(|pair| pair[0].1.agrees_with(&pair[1].1))
+    }
+
+    /// Compares all implementations of `puzzle_part` on `input`. If they disagree, the input is
+    /// shrunk to the smallest input they still disagree on.
+    pub fn check<P: InputPart>(
+        &self,
+        puzzle_part: PuzzlePart,
+        input: &str,
+    ) -> Option<Disagreement<T>> {
+        if self.agree(puzzle_part, input) {
+            return None;
+        }
+
+        let disagree = |candidate: &str| !self.agree(puzzle_part, candidate);
+        let input = match P::parse_parts(&normalize(input)) {
+            Ok(parts) if disagree(&P::render_parts(&parts)) => {
+                P::render_parts(&ddmin(parts, |parts| disagree(&P::render_parts(parts))))
+            }
+            // inputs that can't be split into parts are reported as they are
+            _ => input.to_string(),
+        };
+        let outcomes = self.run(puzzle_part, &input);
+
+        Some(Disagreement {
+            puzzle_part,
+            input,
+            outcomes,
+        })
+    }
+
+    fn implementations_of(
+        &self,
+        puzzle_part: PuzzlePart,
+    ) -> impl Iterator<Item = &Implementation<T>> + '_ {
+        self.implementations
+            .iter()
+            .filter(move |implementation| implementation.puzzle_part == puzzle_part)
+    }
+}
+
+impl<T: PartialEq> Outcome<T> {
+    pub fn agrees_with(&self, other: &Outcome<T>) -> bool {
+        match (self, other) {
+            (Outcome::Answer(lhs), Outcome::Answer(rhs)) => lhs == rhs,
+            (Outcome::Answer(_), _) | (_, Outcome::Answer(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+impl<T: Display> Display for Outcome<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{answer}"),
+            Outcome::Error(err) => write!(f, "error: {err}"),
+            Outcome::Panic => write!(f, "panicked"),
+        }
+    }
+}
+
+impl<T: Display> Display for Disagreement<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "implementations of puzzle part '{:?}' disagree on input:",
+            self.puzzle_part
+        )?;
+        writeln!(f, "{}", self.input)?;
+        for (name, outcome) in &self.outcomes {
+            writeln!(f, "  {name}: {outcome}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares all implementations when `DIFFERENTIAL` is set, either on the puzzle input
+/// (`DIFFERENTIAL=puzzle`) or on the input file at the given path.
+///
+/// Returns `false` if no comparison was requested.
+pub fn differential_from_env<P: InputPart, T: PartialEq + Display>(
+    solutions: &Solutions<T>,
+    puzzle_input: &str,
+    puzzle_part: PuzzlePart,
+) -> eyre::Result<bool> {
+    let input = match env::var("DIFFERENTIAL") {
+        Ok(val) if val == "puzzle" => puzzle_input.to_string(),
+        Ok(path) => {
+            std::fs::read_to_string(&path).wrap_err_with(|| format!("couldn't read {path}"))?
+        }
+        Err(_) => return Ok(false),
+    };
+
+    if let Some(disagreement) = solutions.check::<P>(puzzle_part, &input) {
+        bail!("{disagreement}");
+    }
+
+    let names = solutions.names(puzzle_part).collect::<Vec<_>>();
+    println!(
+        "All implementations of puzzle part '{puzzle_part:?}' agree: {}",
+        names.join(", ")
+    );
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn count_lines(input: &str) -> eyre::Result<usize> {
+        Ok(input.lines().count())
+    }
+
+    fn count_lines_without_x(input: &str) -> eyre::Result<usize> {
+        Ok(input.lines().filter(|line| !line.contains('x')).count())
+    }
+
+    #[test]
+    fn test_check_agreement() {
+        let solutions = Solutions::new()
+            .register(PuzzlePart::One, "lines", count_lines)
+            .register(PuzzlePart::One, "lines again", count_lines)
+            .register(PuzzlePart::Two, "lines without x", count_lines_without_x);
+
+        assert_eq!(solutions.check::<String>(PuzzlePart::One, "a\nx\nb"), None);
+        assert_eq!(solutions.check::<String>(PuzzlePart::Two, "a\nx\nb"), None);
+    }
+
+    #[test]
+    fn test_check_disagreement() {
+        let solutions = Solutions::new()
+            .register(PuzzlePart::One, "lines", count_lines)
+            .register(PuzzlePart::One, "lines without x", count_lines_without_x);
+
+        assert_eq!(
+            solutions.check::<String>(PuzzlePart::One, "a\nb\nx\nc"),
+            Some(Disagreement {
+                puzzle_part: PuzzlePart::One,
+                input: "x".to_string(),
+                outcomes: vec![
+                    ("lines", Outcome::Answer(1)),
+                    ("lines without x", Outcome::Answer(0))
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_outcome_agreement() {
+        assert!(Outcome::Answer(1).agrees_with(&Outcome::Answer(1)));
+        assert!(!Outcome::Answer(1).agrees_with(&Outcome::Answer(2)));
+        assert!(!Outcome::Answer(1).agrees_with(&Outcome::Panic));
+        assert!(Outcome::<u32>::Error("invalid".to_string()).agrees_with(&Outcome::Panic));
+    }
+}
@@ -0,0 +1,202 @@
+use std::env;
+use std::fmt::{self, Write};
+
+use crate::PuzzlePart;
+
+/// Walks through how a day computes its answer, one [`Step`] at a time. Days expose these so
+/// that frontends like the explorer can show what's going on without knowing the day's types.
+pub type Explain = fn(&str, PuzzlePart) -> eyre::Result<Vec<Step>>;
+
+/// How a piece of an explanation should stand out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    None,
+    /// What the current step is about, e.g. the number being checked.
+    Focus,
+    /// What the focused part matched with, e.g. the symbols next to the number.
+    Match,
+    /// Context that doesn't matter for the current step.
+    Dim,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub highlight: Highlight,
+}
+
+impl Span {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::highlighted(text, Highlight::None)
+    }
+
+    pub fn highlighted(text: impl Into<String>, highlight: Highlight) -> Self {
+        Self {
+            text: text.into(),
+            highlight,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub caption: String,
+    pub lines: Vec<Vec<Span>>,
+}
+
+impl Step {
+    pub fn new(caption: impl Into<String>) -> Self {
+        Self {
+            caption: caption.into(),
+            lines: vec![],
+        }
+    }
+
+    pub fn line(mut self, spans: Vec<Span>) -> Self {
+        self.lines.push(spans);
+        self
+    }
+
+    pub fn plain_line(self, text: impl Into<String>) -> Self {
+        self.line(vec![Span::plain(text)])
+    }
+
+    /// Adds the rows `ys` of a character grid, merging neighbouring characters with the same
+    /// highlight into a single span.
+    pub fn grid_lines(
+        mut self,
+        rows: &[&str],
+        ys: impl IntoIterator<Item = usize>,
+        highlight: impl Fn(usize, usize) -> Highlight,
+    ) -> Self {
+        for y in ys {
+            let Some(row) = rows.get(y) else {
+                continue;
+            };
+
+            let mut spans: Vec<Span> = vec![];
+            for (x, c) in row.chars().enumerate() {
+                let highlight = highlight(x, y);
+                match spans.last_mut() {
+                    Some(span) if span.highlight == highlight => span.text.push(c),
+                    _ => spans.push(Span::highlighted(c, highlight)),
+                }
+            }
+            self.lines.push(spans);
+        }
+
+        self
+    }
+}
+
+impl Highlight {
+    /// The SGR parameters of the ANSI escape code coloring a highlight like the pictures do.
+    fn ansi(self) -> Option<&'static str> {
+        match self {
+            Highlight::None => None,
+            Highlight::Focus => Some("1;33"),
+            Highlight::Match => Some("1;32"),
+            Highlight::Dim => Some("2"),
+        }
+    }
+}
+
+impl Step {
+    /// Renders the step for a terminal, with the highlights in color.
+    pub fn to_ansi(&self) -> String {
+        let mut rendered = self.caption.clone();
+        for line in &self.lines {
+            rendered.push('\n');
+            for span in line {
+                match span.highlight.ansi() {
+                    Some(sgr) => write!(rendered, "\x1b[{sgr}m{}\x1b[0m", span.text),
+                    None => write!(rendered, "{}", span.text),
+                }
+                .expect("writing to a string never fails");
+            }
+        }
+
+        rendered
+    }
+}
+
+/// Prints the steps `explain` walks through for `input` if `EXPLAIN=1`, in color unless
+/// `NO_COLOR` is set.
+///
+/// Returns `false` if no explanation was requested.
+pub fn explain_from_env(
+    explain: Explain,
+    input: &str,
+    puzzle_part: PuzzlePart,
+) -> eyre::Result<bool> {
+    if !env::var("EXPLAIN").is_ok_and(|val| val == "1") {
+        return Ok(false);
+    }
+
+    let color = env::var_os("NO_COLOR").is_none_or(|val| val.is_empty());
+    for step in explain(input, puzzle_part)? {
+        if color {
+            println!("{}\n", step.to_ansi());
+        } else {
+            println!("{step}\n");
+        }
+    }
+
+    Ok(true)
+}
+
+/// Renders a step as plain text, marking focused spans with `[..]` and matches with `<..>`, which
+/// is what tests compare against.
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.caption)?;
+        for line in &self.lines {
+            writeln!(f)?;
+            for span in line {
+                match span.highlight {
+                    Highlight::Focus => write!(f, "[{}]", span.text)?,
+                    Highlight::Match => write!(f, "<{}>", span.text)?,
+                    Highlight::None | Highlight::Dim => write!(f, "{}", span.text)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid_lines() {
+        let rows = ["467..114..", "...*......"];
+        let step = Step::new("467 is a part number").grid_lines(&rows, 0..3, |x, y| match (x, y) {
+            (0..=2, 0) => Highlight::Focus,
+            (3, 1) => Highlight::Match,
+            _ => Highlight::None,
+        });
+
+        assert_eq!(step.lines.len(), 2);
+        assert_eq!(step.lines[0].len(), 2);
+        assert_eq!(
+            step.to_string(),
+            "467 is a part number\n[467]..114..\n...<*>......"
+        );
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let step = Step::new("caption").line(vec![
+            Span::plain("a"),
+            Span::highlighted("b", Highlight::Focus),
+            Span::highlighted("c", Highlight::Dim),
+        ]);
+
+        assert_eq!(
+            step.to_ansi(),
+            "caption\na\x1b[1;33mb\x1b[0m\x1b[2mc\x1b[0m"
+        );
+    }
+}
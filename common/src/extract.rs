@@ -0,0 +1,266 @@
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use eyre::{bail, ensure, ContextCompat, WrapErr};
+
+/// Which of the digits found in a text make up its value.
+#[derive(Clone)]
+pub enum Rule {
+    /// The first `first` digits followed by the last `last` ones. The same digits are used twice
+    /// if there are fewer than `first + last`, like a single digit being both the first and the
+    /// last one.
+    Ends { first: usize, last: usize },
+    /// All digits, one after the other.
+    All,
+    /// Any other way of combining the digits, given all of them in order and the radix. Returns
+    /// `None` if the digits have no value.
+    Custom(Combine),
+}
+
+/// Combines all digits of a text in the given radix, see [`Rule::Custom`].
+pub type Combine = Arc<dyn Fn(&[u32], u32) -> Option<u64> + Send + Sync>;
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Ends { first, last } => f
+                .debug_struct("Ends")
+                .field("first", first)
+                .field("last", last)
+                .finish(),
+            Rule::All => write!(f, "All"),
+            Rule::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Parses `first-last`, `first-<n>`, `last-<n>`, `first-<n>-last-<m>` and `all`.
+impl FromStr for Rule {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = |n: &str| -> eyre::Result<usize> {
+            let count = n
+                .parse()
+                .wrap_err_with(|| format!("invalid number of digits: {n}"))?;
+            if count == 0 {
+                bail!("number of digits must be at least 1");
+            }
+
+            Ok(count)
+        };
+
+        let rule = match s.split('-').collect::<Vec<_>>().as_slice() {
+            ["all"] => Rule::All,
+            ["first", "last"] => Rule::Ends { first: 1, last: 1 },
+            ["first", n] => Rule::Ends {
+                first: count(n)?,
+                last: 0,
+            },
+            ["last", n] => Rule::Ends {
+                first: 0,
+                last: count(n)?,
+            },
+            ["first", n, "last", m] => Rule::Ends {
+                first: count(n)?,
+                last: count(m)?,
+            },
+            _ => bail!("unknown extraction rule: {s}"),
+        };
+
+        Ok(rule)
+    }
+}
+
+/// Turns the digits found in a text into a number, e.g. a calibration value from the first and
+/// last digit of a line. Scanners find the digits, the extraction decides which of them count and
+/// how they're combined.
+#[derive(Clone, Debug)]
+pub struct Extraction {
+    pub rule: Rule,
+    /// The base the digits are written in, from 2 to 36.
+    pub radix: u32,
+}
+
+impl Default for Extraction {
+    /// The first and last digit in base 10.
+    fn default() -> Self {
+        Self::new(Rule::Ends { first: 1, last: 1 })
+    }
+}
+
+impl Extraction {
+    /// Base 10 unless changed with [`Extraction::radix`].
+    pub fn new(rule: Rule) -> Self {
+        Self { rule, radix: 10 }
+    }
+
+    pub fn radix(mut self, radix: u32) -> eyre::Result<Self> {
+        ensure!(
+            (2..=36).contains(&radix),
+            "radix {radix} isn't between 2 and 36"
+        );
+        self.radix = radix;
+        Ok(self)
+    }
+
+    /// The extraction given via the `EXTRACT` env var, see [`Rule`]'s `FromStr` impl, in the base
+    /// given via `RADIX`. Both default to the [`Default`].
+    pub fn from_env() -> eyre::Result<Self> {
+        let mut extraction = Self::default();
+        if let Ok(rule) = env::var("EXTRACT") {
+            extraction.rule = rule.parse()?;
+        }
+        if let Ok(radix) = env::var("RADIX") {
+            let radix = radix
+                .parse()
+                .wrap_err_with(|| format!("invalid radix: {radix}"))?;
+            extraction = extraction.radix(radix)?;
+        }
+
+        Ok(extraction)
+    }
+
+    /// How many digits from the start and from the end the rule needs, so scanners can skip the
+    /// digits in between. `None` if it needs all digits.
+    pub fn ends(&self) -> Option<(usize, usize)> {
+        match self.rule {
+            Rule::Ends { first, last } => Some((first, last)),
+            Rule::All | Rule::Custom(_) => None,
+        }
+    }
+
+    /// The value of a text with `digits`, all digits found in it in order. `None` if there are no
+    /// digits, fails if the value doesn't fit into a `u64`.
+    pub fn extract(&self, digits: &[u32]) -> eyre::Result<Option<u64>> {
+        match &self.rule {
+            Rule::Ends { first, last } => {
+                let first = &digits[..(*first).min(digits.len())];
+                let last = &digits[digits.len().saturating_sub(*last)..];
+                self.extract_ends(first, last)
+            }
+            Rule::All => self.concat(digits.iter().copied()),
+            Rule::Custom(combine) => Ok(combine(digits, self.radix)),
+        }
+    }
+
+    /// Same as [`Extraction::extract`] for rules with [`Extraction::ends`], given only the digits
+    /// at the ends: up to `first` from the start and up to `last` from the end, in order.
+    pub fn extract_ends(&self, first: &[u32], last: &[u32]) -> eyre::Result<Option<u64>> {
+        self.concat(first.iter().chain(last).copied())
+    }
+
+    /// Writes the numbers one after the other in the radix. Numbers that take more than one digit
+    /// are written out in full, so in base 10 `10` and `3` make `103`.
+    pub fn concat(&self, numbers: impl IntoIterator<Item = u32>) -> eyre::Result<Option<u64>> {
+        let radix = u64::from(self.radix);
+        numbers
+            .into_iter()
+            .try_fold(None, |value: Option<u64>, number| {
+                let number = u64::from(number);
+                let mut shift = radix;
+                while shift <= number {
+                    shift = shift.checked_mul(radix).wrap_err("value too large")?;
+                }
+
+                let value = value
+                    .unwrap_or(0)
+                    .checked_mul(shift)
+                    .and_then(|value| value.checked_add(number))
+                    .wrap_err("value too large")?;
+                Ok(Some(value))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_extract() -> eyre::Result<()> {
+        let digits = [1, 2, 3, 4, 5];
+        let extract = |rule: &str| -> eyre::Result<Option<u64>> {
+            Extraction::new(rule.parse()?).extract(&digits)
+        };
+
+        assert_eq!(extract("first-last")?, Some(15));
+        assert_eq!(extract("first-2")?, Some(12));
+        assert_eq!(extract("last-3")?, Some(345));
+        assert_eq!(extract("first-2-last-2")?, Some(1245));
+        assert_eq!(extract("first-4-last-4")?, Some(12342345));
+        assert_eq!(extract("all")?, Some(12345));
+
+        let single = Extraction::default();
+        assert_eq!(single.extract(&[7])?, Some(77));
+        assert_eq!(single.extract(&[])?, None);
+        assert_eq!(single.extract(&[10, 3])?, Some(103));
+
+        let err = "first-x".parse::<Rule>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid number of digits: x");
+        assert!("middle".parse::<Rule>().is_err());
+        for rule in ["first-0", "last-0", "first-0-last-0", "first-2-last-0"] {
+            let err = rule.parse::<Rule>().unwrap_err();
+            assert_eq!(err.to_string(), "number of digits must be at least 1");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_hex() -> eyre::Result<()> {
+        let hex = Extraction::default().radix(16)?;
+        assert_eq!(hex.extract(&[0xa, 3, 0xf])?, Some(0xaf));
+        // 16 is written as 10 in base 16
+        assert_eq!(hex.extract(&[1, 16])?, Some(0x110));
+
+        assert!(Extraction::default().radix(37).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_custom() -> eyre::Result<()> {
+        let sum = Extraction::new(Rule::Custom(Arc::new(|digits, _| {
+            Some(digits.iter().map(|&digit| u64::from(digit)).sum())
+        })));
+        assert_eq!(sum.extract(&[1, 2, 3])?, Some(6));
+        assert_eq!(sum.ends(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_overflow() {
+        let all = Extraction::new(Rule::All);
+        let err = all.extract(&[9; 21]).unwrap_err();
+        assert_eq!(err.to_string(), "value too large");
+        assert_eq!(
+            all.extract(&[9; 19]).ok(),
+            Some(Some(9_999_999_999_999_999_999))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_extract_ends_matches_extract(
+            digits in prop::collection::vec(0u32..16, 0..10),
+            first in 0usize..4,
+            last in 0usize..4,
+        ) {
+            let extraction = Extraction::new(Rule::Ends { first, last }).radix(16).unwrap();
+            let ends = (
+                &digits[..first.min(digits.len())],
+                &digits[digits.len().saturating_sub(last)..],
+            );
+
+            prop_assert_eq!(
+                extraction.extract_ends(ends.0, ends.1).ok(),
+                extraction.extract(&digits).ok()
+            );
+        }
+    }
+}
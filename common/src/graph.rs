@@ -0,0 +1,317 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A small id standing in for a key of an [`Interner`], cheap to copy and to index with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Hands out consecutive [`NodeId`]s for keys, the same id for the same key.
+#[derive(Clone, Debug)]
+pub struct Interner<K> {
+    ids: HashMap<K, NodeId>,
+    keys: Vec<K>,
+}
+
+// the ids follow from the order of the keys
+impl<K: PartialEq> PartialEq for Interner<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys
+    }
+}
+
+impl<K: Eq> Eq for Interner<K> {}
+
+impl<K> Default for Interner<K> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            keys: vec![],
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> Interner<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, key: K) -> NodeId {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+
+        let id = NodeId(u32::try_from(self.keys.len()).expect("too many keys to intern"));
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        id
+    }
+
+    pub fn get(&self, key: &K) -> Option<NodeId> {
+        self.ids.get(key).copied()
+    }
+
+    /// The key of an id handed out by this interner.
+    pub fn key(&self, id: NodeId) -> &K {
+        &self.keys[id.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// All ids with their keys, in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &K)> {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(idx, key)| (NodeId(idx as u32), key))
+    }
+}
+
+/// A directed graph stored as adjacency lists, with nodes keyed by `K` and edges carrying `E`,
+/// e.g. a weight or a label. The edges of a node keep the order they were added in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph<K, E> {
+    nodes: Interner<K>,
+    edges: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<K, E> Default for Graph<K, E> {
+    fn default() -> Self {
+        Self {
+            nodes: Interner::default(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, E> Graph<K, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, key: K) -> NodeId {
+        let id = self.nodes.intern(key);
+        if self.edges.len() <= id.index() {
+            self.edges.resize_with(id.index() + 1, Vec::new);
+        }
+        id
+    }
+
+    /// Adds an edge, adding its nodes first if they don't exist yet.
+    pub fn add_edge(&mut self, from: K, to: K, edge: E) -> (NodeId, NodeId) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from.index()].push((to, edge));
+        (from, to)
+    }
+
+    pub fn id(&self, key: &K) -> Option<NodeId> {
+        self.nodes.get(key)
+    }
+
+    pub fn key(&self, id: NodeId) -> &K {
+        self.nodes.key(id)
+    }
+
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, E)] {
+        &self.edges[id.index()]
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &K)> {
+        self.nodes.iter()
+    }
+}
+
+/// The path with the fewest edges from `start` to a node for which `is_goal` holds, both ends
+/// included.
+pub fn bfs<K, E>(
+    graph: &Graph<K, E>,
+    start: NodeId,
+    is_goal: impl Fn(NodeId) -> bool,
+) -> Option<Vec<NodeId>> {
+    let mut previous = vec![None; graph.edges.len()];
+    let mut queue = VecDeque::from([start]);
+    previous[start.index()] = Some(start);
+
+    while let Some(cur) = queue.pop_front() {
+        if is_goal(cur) {
+            return Some(path(&previous, start, cur));
+        }
+
+        for &(next, _) in &graph.edges[cur.index()] {
+            if previous[next.index()].is_none() {
+                previous[next.index()] = Some(cur);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// The cheapest path from `start` to a node for which `is_goal` holds, weighing the edges by
+/// their data, along with its cost.
+pub fn dijkstra<K, W>(
+    graph: &Graph<K, W>,
+    start: NodeId,
+    is_goal: impl Fn(NodeId) -> bool,
+) -> Option<(W, Vec<NodeId>)>
+where
+    W: Copy + Ord + Default + Add<Output = W>,
+{
+    a_star(graph, start, is_goal, |_| W::default())
+}
+
+/// Same as [`dijkstra`], but searches towards the goal first as guided by `heuristic`, which
+/// estimates the remaining cost from a node. The path is only guaranteed to be the cheapest if
+/// the estimates never exceed the actual costs.
+pub fn a_star<K, W>(
+    graph: &Graph<K, W>,
+    start: NodeId,
+    is_goal: impl Fn(NodeId) -> bool,
+    heuristic: impl Fn(NodeId) -> W,
+) -> Option<(W, Vec<NodeId>)>
+where
+    W: Copy + Ord + Default + Add<Output = W>,
+{
+    let mut costs = vec![None; graph.edges.len()];
+    let mut previous = vec![None; graph.edges.len()];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(start), W::default(), start))]);
+    costs[start.index()] = Some(W::default());
+    previous[start.index()] = Some(start);
+
+    while let Some(Reverse((_, cost, cur))) = queue.pop() {
+        if costs[cur.index()].is_some_and(|best| best < cost) {
+            continue;
+        }
+        if is_goal(cur) {
+            return Some((cost, path(&previous, start, cur)));
+        }
+
+        for &(next, weight) in &graph.edges[cur.index()] {
+            let next_cost = cost + weight;
+            if costs[next.index()].is_none_or(|best| next_cost < best) {
+                costs[next.index()] = Some(next_cost);
+                previous[next.index()] = Some(cur);
+                queue.push(Reverse((next_cost + heuristic(next), next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+fn path(previous: &[Option<NodeId>], start: NodeId, end: NodeId) -> Vec<NodeId> {
+    let mut path = vec![end];
+    let mut cur = end;
+    while cur != start {
+        cur = previous[cur.index()].expect("visited nodes have a predecessor");
+        path.push(cur);
+    }
+    path.reverse();
+
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// ```text
+    /// a -1-> b -1-> c -1-> d
+    ///  \------------5------^
+    /// ```
+    fn example() -> Graph<char, u32> {
+        let mut graph = Graph::new();
+        graph.add_edge('a', 'b', 1);
+        graph.add_edge('b', 'c', 1);
+        graph.add_edge('c', 'd', 1);
+        graph.add_edge('a', 'd', 5);
+        graph.add_node('e');
+        graph
+    }
+
+    fn keys(graph: &Graph<char, u32>, path: &[NodeId]) -> String {
+        path.iter().map(|&id| graph.key(id)).collect()
+    }
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+
+        assert_eq!(interner.intern("a"), a);
+        assert_ne!(a, b);
+        assert_eq!(interner.key(b), &"b");
+        assert_eq!(interner.get(&"c"), None);
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let graph = example();
+        let [a, d, e] = ['a', 'd', 'e'].map(|key| graph.id(&key).expect("node exists"));
+
+        let path = bfs(&graph, a, |id| id == d).expect("d is reachable");
+        assert_eq!(keys(&graph, &path), "ad");
+        assert_eq!(bfs(&graph, a, |id| id == e), None);
+        assert_eq!(bfs(&graph, a, |id| id == a), Some(vec![a]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let graph = example();
+        let [a, d] = ['a', 'd'].map(|key| graph.id(&key).expect("node exists"));
+
+        let (cost, path) = dijkstra(&graph, a, |id| id == d).expect("d is reachable");
+        assert_eq!(cost, 3);
+        assert_eq!(keys(&graph, &path), "abcd");
+    }
+
+    #[test]
+    fn test_a_star() {
+        // a grid where every step costs 1, so the Manhattan distance is a lower bound
+        let mut graph = Graph::new();
+        for x in 0..5i32 {
+            for y in 0..5i32 {
+                for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                    let next = (x + dx, y + dy);
+                    if (0..5).contains(&next.0) && (0..5).contains(&next.1) && next != (2, 2) {
+                        graph.add_edge((x, y), next, 1);
+                    }
+                }
+            }
+        }
+        let start = graph.id(&(0, 2)).expect("node exists");
+        let goal = graph.id(&(4, 2)).expect("node exists");
+        let manhattan = |id| {
+            let (x, y): (i32, i32) = *graph.key(id);
+            (4 - x).abs() + (2 - y).abs()
+        };
+
+        let (cost, path) = a_star(&graph, start, |id| id == goal, manhattan).expect("reachable");
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert_eq!(
+            dijkstra(&graph, start, |id| id == goal).map(|(cost, _)| cost),
+            Some(6)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use eyre::WrapErr;
+
+use crate::normalize::BYTE_ORDER_MARK;
+
+/// Reads a puzzle input line by line, only ever holding a single line in memory.
+///
+/// Lines are split like [`str::lines`] splits them and normalized like
+/// [`normalize`](crate::normalize::normalize) normalizes them, except for the changes that need
+/// the whole input: blank lines around the input and common indentation are kept.
+pub struct Lines<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+        }
+    }
+
+    /// The next line without its line ending, or `None` once the input is exhausted.
+    pub fn next_line(&mut self) -> eyre::Result<Option<&str>> {
+        self.line.clear();
+        let read = self
+            .reader
+            .read_line(&mut self.line)
+            .wrap_err_with(|| format!("couldn't read line {}", self.line_number + 1))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+
+        let mut line = self.line.trim_end();
+        if self.line_number == 1 {
+            line = line.strip_prefix(BYTE_ORDER_MARK).unwrap_or(line);
+        }
+
+        Ok(Some(line))
+    }
+
+    /// The 1-based number of the line last returned by [`Lines::next_line`].
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+/// Folds over all lines of `reader`. Errors of `f` are annotated with the line they occurred in.
+pub fn try_fold_lines<R: BufRead, B>(
+    reader: R,
+    init: B,
+    mut f: impl FnMut(B, &str) -> eyre::Result<B>,
+) -> eyre::Result<B> {
+    let mut lines = Lines::new(reader);
+    let mut acc = init;
+    while let Some(line) = lines.next_line()? {
+        acc = f(acc, line).wrap_err_with(|| format!("in line {}", lines.line_number()))?;
+    }
+
+    Ok(acc)
+}
+
+/// The puzzle input given via the `INPUT` env var: a path to a file, or `-` for stdin. Returns
+/// `None` if it isn't set, in which case the bundled puzzle input should be used.
+pub fn input_from_env() -> eyre::Result<Option<Box<dyn BufRead>>> {
+    let reader: Box<dyn BufRead> = match env::var("INPUT") {
+        Ok(path) if path == "-" => Box::new(io::stdin().lock()),
+        Ok(path) => Box::new(BufReader::new(
+            File::open(&path).wrap_err_with(|| format!("couldn't open input {path}"))?,
+        )),
+        Err(_) => return Ok(None),
+    };
+
+    Ok(Some(reader))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::normalize::normalize;
+
+    #[test]
+    fn test_lines_match_normalized_lines() -> eyre::Result<()> {
+        for input in [
+            "",
+            "a",
+            "a\n",
+            "a\r\nb",
+            "a\n\nb\n",
+            "a\r\n\r\nb",
+            "\u{feff}a \nb\t",
+        ] {
+            let mut lines = Lines::new(input.as_bytes());
+            let mut read = vec![];
+            while let Some(line) = lines.next_line()? {
+                read.push(line.to_string());
+            }
+
+            let expected = normalize(input)
+                .lines()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            assert_eq!(read, expected, "input: {input:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_fold_lines() -> eyre::Result<()> {
+        let sum = try_fold_lines("1\n2\n3\n".as_bytes(), 0, |sum, line| {
+            Ok(sum + line.parse::<u32>()?)
+        })?;
+        assert_eq!(sum, 6);
+
+        let err = try_fold_lines("1\nx\n3".as_bytes(), 0, |sum, line| {
+            Ok(sum + line.parse::<u32>()?)
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "in line 2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut lines = Lines::new(&b"a\n\xff\n"[..]);
+        assert!(matches!(lines.next_line(), Ok(Some("a"))));
+
+        let err = lines.next_line().unwrap_err();
+        assert_eq!(err.to_string(), "couldn't read line 2");
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use eyre::WrapErr;
+use serde::Deserialize;
+
+/// A private leaderboard as served by `/<year>/leaderboard/private/view/<id>.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// Missing for anonymous members.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    pub last_star_ts: i64,
+    /// The stars by day and part.
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Star {
+    /// Seconds since the Unix epoch.
+    pub get_star_ts: i64,
+    /// Orders stars collected within the same second.
+    #[serde(default)]
+    pub star_index: u64,
+}
+
+/// A member's place on the leaderboard.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Standing<'a> {
+    /// Members with the same score share a rank.
+    pub rank: usize,
+    pub member: &'a Member,
+    pub score: u32,
+}
+
+/// How long a member took from the first to the second star of a day.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delta<'a> {
+    pub day: u8,
+    pub member: &'a Member,
+    pub seconds: i64,
+}
+
+impl FromStr for Leaderboard {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).wrap_err("invalid leaderboard JSON")
+    }
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star(&self, day: u8, part: u8) -> Option<&Star> {
+        self.completion_day_level.get(&day)?.get(&part)
+    }
+}
+
+impl Leaderboard {
+    /// The latest day anyone got a star on.
+    pub fn last_day(&self) -> u8 {
+        self.members
+            .values()
+            .filter_map(|member| member.completion_day_level.keys().max())
+            .max()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Computes the local scores: for every star, the first member to get it gets as many points
+    /// as there are members, the second one point less and so on.
+    pub fn local_scores(&self) -> BTreeMap<u64, u32> {
+        let members = self.members.len() as u32;
+        let mut scores = self
+            .members
+            .values()
+            .map(|member| (member.id, 0))
+            .collect::<BTreeMap<_, _>>();
+
+        for day in 1..=self.last_day() {
+            for part in 1..=2 {
+                let mut stars = self
+                    .members
+                    .values()
+                    .filter_map(|member| Some((member.id, member.star(day, part)?)))
+                    .collect::<Vec<_>>();
+                stars.sort_by_key(|(_, star)| (star.get_star_ts, star.star_index));
+
+                for (position, (id, _)) in stars.into_iter().enumerate() {
+                    *scores.entry(id).or_default() += members - position as u32;
+                }
+            }
+        }
+
+        scores
+    }
+
+    /// The members by descending local score. Ties are broken by who got their last star first.
+    pub fn standings(&self) -> Vec<Standing<'_>> {
+        let scores = self.local_scores();
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by_key(|member| {
+            (
+                u32::MAX - scores[&member.id],
+                member.last_star_ts,
+                member.id,
+            )
+        });
+
+        let mut standings: Vec<Standing<'_>> = vec![];
+        for (idx, member) in members.into_iter().enumerate() {
+            let score = scores[&member.id];
+            let rank = match standings.last() {
+                Some(previous) if previous.score == score => previous.rank,
+                _ => idx + 1,
+            };
+            standings.push(Standing {
+                rank,
+                member,
+                score,
+            });
+        }
+
+        standings
+    }
+
+    /// The time between the two stars of every day a member got both on, fastest first.
+    pub fn deltas(&self) -> Vec<Delta<'_>> {
+        let mut deltas = self
+            .members
+            .values()
+            .flat_map(|member| {
+                (1..=self.last_day()).filter_map(move |day| {
+                    let first = member.star(day, 1)?;
+                    let second = member.star(day, 2)?;
+                    Some(Delta {
+                        day,
+                        member,
+                        seconds: second.get_star_ts - first.get_star_ts,
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        deltas.sort_by_key(|delta| (delta.day, delta.seconds, delta.member.id));
+
+        deltas
+    }
+
+    /// Renders the standings like the leaderboard page does: `*` for days with both stars, `+` for
+    /// days with only the first one.
+    pub fn render_standings(&self) -> String {
+        let days = 1..=self.last_day();
+        let mut table = String::new();
+
+        if self.last_day() >= 10 {
+            let tens = days
+                .clone()
+                .map(|day| {
+                    if day >= 10 {
+                        char::from(b'0' + day / 10)
+                    } else {
+                        ' '
+                    }
+                })
+                .collect::<String>();
+            writeln!(table, "{:18}{tens}", "").expect("writing to a string never fails");
+        }
+        let units = days
+            .clone()
+            .map(|day| char::from(b'0' + day % 10))
+            .collect::<String>();
+        writeln!(table, "{:>11} {:>5}  {units}", "Score", "Stars")
+            .expect("writing to a string never fails");
+
+        for standing in self.standings() {
+            let member = standing.member;
+            let marks = days
+                .clone()
+                .map(|day| match (member.star(day, 1), member.star(day, 2)) {
+                    (Some(_), Some(_)) => '*',
+                    (Some(_), None) => '+',
+                    _ => '.',
+                })
+                .collect::<String>();
+            writeln!(
+                table,
+                "{:>3}) {:>5} {:>5}  {marks}  {}",
+                standing.rank,
+                standing.score,
+                member.stars,
+                member.display_name()
+            )
+            .expect("writing to a string never fails");
+        }
+
+        table
+    }
+
+    /// Renders the time every member needed for the second star, per day.
+    pub fn render_deltas(&self) -> String {
+        let mut table = String::new();
+        let mut current_day = None;
+
+        for delta in self.deltas() {
+            if current_day != Some(delta.day) {
+                writeln!(table, "Day {}", delta.day).expect("writing to a string never fails");
+                current_day = Some(delta.day);
+            }
+            writeln!(
+                table,
+                "  {:>9}  {}",
+                format_seconds(delta.seconds),
+                delta.member.display_name()
+            )
+            .expect("writing to a string never fails");
+        }
+
+        table
+    }
+}
+
+fn format_seconds(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/leaderboard.json");
+
+    #[test]
+    fn test_leaderboard_parse() -> eyre::Result<()> {
+        let leaderboard = Leaderboard::from_str(FIXTURE)?;
+        assert_eq!(leaderboard.members.len(), 3);
+        assert_eq!(leaderboard.last_day(), 2);
+
+        let anonymous = &leaderboard.members["1003"];
+        assert_eq!(anonymous.display_name(), "(anonymous user #1003)");
+        assert_eq!(
+            anonymous.star(1, 1),
+            Some(&Star {
+                get_star_ts: 1701450000,
+                star_index: 20
+            })
+        );
+
+        let err = Leaderboard::from_str("{}").unwrap_err();
+        assert_eq!(err.to_string(), "invalid leaderboard JSON");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_scores_match_served_scores() -> eyre::Result<()> {
+        let leaderboard = Leaderboard::from_str(FIXTURE)?;
+        let served = leaderboard
+            .members
+            .values()
+            .map(|member| (member.id, member.local_score))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(leaderboard.local_scores(), served);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_standings() -> eyre::Result<()> {
+        let mut leaderboard = Leaderboard::from_str(FIXTURE)?;
+        let ranks = |leaderboard: &Leaderboard| {
+            leaderboard
+                .standings()
+                .iter()
+                .map(|standing| (standing.rank, standing.member.id, standing.score))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ranks(&leaderboard),
+            [(1, 1001, 11), (2, 1002, 7), (3, 1003, 1)]
+        );
+
+        // Bob getting the last star of day 2 first makes it a tie
+        let bob = leaderboard
+            .members
+            .get_mut("1002")
+            .expect("Bob is a member");
+        bob.completion_day_level.entry(2).or_default().insert(
+            2,
+            Star {
+                get_star_ts: 1701493900,
+                star_index: 29,
+            },
+        );
+        assert_eq!(
+            ranks(&leaderboard),
+            [(1, 1001, 10), (1, 1002, 10), (3, 1003, 1)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deltas() -> eyre::Result<()> {
+        let leaderboard = Leaderboard::from_str(FIXTURE)?;
+        let deltas = leaderboard
+            .deltas()
+            .iter()
+            .map(|delta| (delta.day, delta.member.id, delta.seconds))
+            .collect::<Vec<_>>();
+
+        assert_eq!(deltas, [(1, 1001, 300), (1, 1002, 1100), (2, 1001, 500)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> eyre::Result<()> {
+        let leaderboard = Leaderboard::from_str(FIXTURE)?;
+
+        assert_eq!(
+            leaderboard.render_standings(),
+            "      Score Stars  12
+  1)    11     4  **  Alice
+  2)     7     3  *+  Bob
+  3)     1     1  +.  (anonymous user #1003)
+"
+        );
+        assert_eq!(
+            leaderboard.render_deltas(),
+            "\
+Day 1
+    0:05:00  Alice
+    0:18:20  Bob
+Day 2
+    0:08:20  Alice
+"
+        );
+
+        Ok(())
+    }
+}
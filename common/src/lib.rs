@@ -1,5 +1,20 @@
+use std::str::FromStr;
+
 use eyre::bail;
 
+pub mod cycle;
+pub mod differential;
+pub mod explain;
+pub mod extract;
+pub mod graph;
+pub mod input;
+pub mod leaderboard;
+pub mod minimize;
+pub mod normalize;
+pub mod snapshot;
+pub mod svg;
+pub mod trace;
+
 #[derive(Copy, Clone, Debug, PartialOrd, PartialEq, Eq, Ord)]
 pub enum PuzzlePart {
     One,
@@ -8,11 +23,23 @@ pub enum PuzzlePart {
 
 impl PuzzlePart {
     pub fn from_env() -> eyre::Result<PuzzlePart> {
-        let puzzle_part = match std::env::var("PUZZLE_PART") {
-            Ok(val) if val.eq_ignore_ascii_case("one") => PuzzlePart::One,
-            Ok(val) if val.eq_ignore_ascii_case("two") => PuzzlePart::Two,
-            Err(_) => PuzzlePart::One,
-            Ok(val) => bail!("Unknown puzzle_part: {val}"),
+        match std::env::var("PUZZLE_PART") {
+            Ok(val) => val.parse(),
+            Err(_) => Ok(PuzzlePart::One),
+        }
+    }
+}
+
+impl FromStr for PuzzlePart {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let puzzle_part = match s {
+            "1" => PuzzlePart::One,
+            "2" => PuzzlePart::Two,
+            _ if s.eq_ignore_ascii_case("one") => PuzzlePart::One,
+            _ if s.eq_ignore_ascii_case("two") => PuzzlePart::Two,
+            _ => bail!("Unknown puzzle_part: {s}"),
         };
 
         Ok(puzzle_part)
@@ -0,0 +1,293 @@
+use std::cell::Cell;
+use std::env;
+use std::fmt::Display;
+use std::io::Write;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Command, Stdio};
+use std::sync::Once;
+use std::thread;
+
+use eyre::{bail, ContextCompat, WrapErr};
+
+use crate::normalize::normalize;
+
+/// A part of a puzzle input that can be removed without touching the rest of the input, e.g. a
+/// single line or a single mapping of a section.
+pub trait InputPart: Clone + Sized {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>>;
+
+    fn render_parts(parts: &[Self]) -> String;
+}
+
+/// Inputs without any further structure are minimized line by line.
+impl InputPart for String {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        parts.join("\n")
+    }
+}
+
+/// What makes an input "failing" while minimizing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The solver panics.
+    Panic,
+    /// The solver returns an error or panics.
+    Error,
+    /// The solver's answer differs from what the given shell command prints when the input is
+    /// passed via stdin. Inputs for which either side fails don't count as a disagreement.
+    Disagreement { reference: String },
+}
+
+impl Failure {
+    pub fn from_env() -> eyre::Result<Failure> {
+        let failure = match env::var("MINIMIZE_FAILURE") {
+            Ok(val) if val.eq_ignore_ascii_case("panic") => Failure::Panic,
+            Ok(val) if val.eq_ignore_ascii_case("error") => Failure::Error,
+            Ok(val) => match val.split_once('=') {
+                Some(("reference", reference)) => Failure::Disagreement {
+                    reference: reference.to_string(),
+                },
+                _ => bail!("Unknown minimize failure: {val}"),
+            },
+            Err(_) => Failure::Panic,
+        };
+
+        Ok(failure)
+    }
+
+    pub fn reproduces<T: Display>(
+        &self,
+        input: &str,
+        solve: impl FnOnce(&str) -> eyre::Result<T>,
+    ) -> bool {
+        let res = catch_panic(|| solve(input));
+
+        match self {
+            Failure::Panic => res.is_err(),
+            Failure::Error => !matches!(res, Ok(Ok(_))),
+            Failure::Disagreement { reference } => match (res, run_reference(reference, input)) {
+                (Ok(Ok(answer)), Ok(expected)) => answer.to_string() != expected,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Shrinks the failing input read from the file at `MINIMIZE`, printing the smallest input that
+/// still reproduces the failure selected by `MINIMIZE_FAILURE`.
+///
+/// Returns `false` if no minimization was requested.
+pub fn minimize_from_env<P: InputPart, T: Display>(
+    solve: impl Fn(&str) -> eyre::Result<T>,
+) -> eyre::Result<bool> {
+    let Ok(path) = env::var("MINIMIZE") else {
+        return Ok(false);
+    };
+
+    let input = std::fs::read_to_string(&path).wrap_err_with(|| format!("couldn't read {path}"))?;
+    let failure = Failure::from_env()?;
+    let minimized = minimize::<P>(&input, |candidate| failure.reproduces(candidate, &solve))?;
+    println!("{minimized}");
+
+    Ok(true)
+}
+
+/// Shrinks `input` to a smaller input for which `is_failing` still holds, removing parts as
+/// parsed by `P` and rendering the remaining ones back into an input.
+pub fn minimize<P: InputPart>(
+    input: &str,
+    mut is_failing: impl FnMut(&str) -> bool,
+) -> eyre::Result<String> {
+    let parts = P::parse_parts(&normalize(input)).wrap_err("couldn't parse input to minimize")?;
+    if !is_failing(&P::render_parts(&parts)) {
+        bail!("input doesn't reproduce the failure");
+    }
+
+    let minimized = ddmin(parts, |parts| is_failing(&P::render_parts(parts)));
+    Ok(P::render_parts(&minimized))
+}
+
+/// Zeller's delta debugging: returns a subsequence of `items` for which `is_failing` holds and
+/// from which no single item can be removed without losing the failure.
+///
+/// `is_failing` must hold for `items`.
+pub fn ddmin<T: Clone>(mut items: Vec<T>, mut is_failing: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut granularity = 2;
+
+    while items.len() >= 2 {
+        let chunks = chunks(items.len(), granularity);
+
+        if let Some(chunk) = chunks
+            .iter()
+            .find(|chunk| is_failing(&items[(*chunk).clone()]))
+        {
+            items = items[chunk.clone()].to_vec();
+            granularity = 2;
+            continue;
+        }
+
+        let complement = chunks.iter().find_map(|chunk| {
+            let complement = [&items[..chunk.start], &items[chunk.end..]].concat();
+            is_failing(&complement).then_some(complement)
+        });
+        if let Some(complement) = complement {
+            items = complement;
+            granularity = (granularity - 1).max(2);
+            continue;
+        }
+
+        if granularity >= items.len() {
+            break;
+        }
+        granularity = (granularity * 2).min(items.len());
+    }
+
+    if items.len() == 1 && is_failing(&[]) {
+        items.clear();
+    }
+
+    items
+}
+
+fn chunks(len: usize, granularity: usize) -> Vec<Range<usize>> {
+    let chunk_len = len.div_ceil(granularity);
+    (0..len)
+        .step_by(chunk_len)
+        .map(|start| start..(start + chunk_len).min(len))
+        .collect()
+}
+
+/// Returns whether `f` panics, without printing the panic message.
+pub fn panics<T>(f: impl FnOnce() -> T) -> bool {
+    catch_panic(f).is_err()
+}
+
+thread_local! {
+    /// Whether panics on this thread are caught by [`catch_panic`] and shouldn't be printed.
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Catches a panic of `f` like [`panic::catch_unwind`], but without printing the panic message.
+/// Panics on other threads are still printed.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> thread::Result<T> {
+    // the panic hook is global, swapping it for every call would race with other threads, so a
+    // single hook wrapping the previous one is installed that only stays quiet where asked to
+    static SILENCING_HOOK: Once = Once::new();
+    SILENCING_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCED.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+
+    // restoring instead of resetting the flag keeps nested calls quiet
+    let was_silenced = SILENCED.with(|silenced| silenced.replace(true));
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    SILENCED.with(|silenced| silenced.set(was_silenced));
+
+    res
+}
+
+fn run_reference(reference: &str, input: &str) -> eyre::Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(reference)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .wrap_err("couldn't start reference")?;
+
+    child
+        .stdin
+        .take()
+        .wrap_err("reference has no stdin")?
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("reference failed with {}", output.status);
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ddmin_single_culprit() {
+        let res = ddmin((0..100).collect(), |items| items.contains(&42));
+        assert_eq!(res, vec![42]);
+    }
+
+    #[test]
+    fn test_ddmin_multiple_culprits() {
+        let res = ddmin((0..100).collect(), |items| {
+            items.contains(&3) && items.contains(&50) && items.contains(&99)
+        });
+        assert_eq!(res, vec![3, 50, 99]);
+    }
+
+    #[test]
+    fn test_ddmin_empty_input_fails() {
+        let res = ddmin(vec![1, 2, 3], |_| true);
+        assert_eq!(res, Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_minimize_lines() -> eyre::Result<()> {
+        let res = minimize::<String>("a\nb\nboom\nc\nd", |input| {
+            panics(|| {
+                if input.contains("boom") {
+                    panic!("boom");
+                }
+            })
+        })?;
+        assert_eq!(res, "boom");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_not_failing() {
+        let res = minimize::<String>("a\nb", |_| false);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_panics_on_several_threads() {
+        let threads = (0..8)
+            .map(|idx| {
+                thread::spawn(move || {
+                    (0..100).all(|_| {
+                        panics(|| panic!("expected"))
+                            && !panics(|| idx)
+                            && !SILENCED.with(Cell::get)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            assert!(thread.join().unwrap());
+        }
+        assert!(catch_panic(|| panics(|| panic!("nested")) && SILENCED.with(Cell::get)).unwrap());
+    }
+
+    #[test]
+    fn test_failure_disagreement() {
+        let failure = Failure::Disagreement {
+            reference: "wc -l".to_string(),
+        };
+
+        assert!(!failure.reproduces("a\nb\n", |input| Ok(input.lines().count())));
+        assert!(failure.reproduces("a\nb\n", |_| Ok(3)));
+    }
+}
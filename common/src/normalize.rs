@@ -0,0 +1,240 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+
+pub(crate) const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Something [`normalize`] changed about an input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    ByteOrderMark,
+    CrLf { lines: usize },
+    TrailingWhitespace { lines: usize },
+    LeadingBlankLines { lines: usize },
+    TrailingBlankLines { lines: usize },
+    Dedent { columns: usize },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::ByteOrderMark => write!(f, "removed the UTF-8 byte order mark"),
+            Change::CrLf { lines } => write!(f, "converted {lines} CRLF line endings to LF"),
+            Change::TrailingWhitespace { lines } => {
+                write!(f, "removed trailing whitespace from {lines} lines")
+            }
+            Change::LeadingBlankLines { lines } => write!(f, "removed {lines} leading blank lines"),
+            Change::TrailingBlankLines { lines } => {
+                write!(f, "removed {lines} trailing blank lines")
+            }
+            Change::Dedent { columns } => write!(f, "removed {columns} columns of indentation"),
+        }
+    }
+}
+
+/// An input after [`normalize`], along with what had to be changed to get there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalized<'a> {
+    text: Cow<'a, str>,
+    changes: Vec<Change>,
+}
+
+impl Normalized<'_> {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+}
+
+impl Deref for Normalized<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Brings an input into the shape the parsers expect: no byte order mark, LF line endings, no
+/// trailing whitespace, no blank lines around the input and no common indentation, which test
+/// fixtures written as indented string literals tend to have. Blank lines within the input are
+/// kept, as they separate sections for some days. The final line ending is kept if there is one.
+///
+/// The changes are also logged at the info level, so `TRACE=info` shows what was changed.
+pub fn normalize(input: &str) -> Normalized<'_> {
+    let mut changes = vec![];
+
+    let text = match input.strip_prefix(BYTE_ORDER_MARK) {
+        Some(text) => {
+            changes.push(Change::ByteOrderMark);
+            text
+        }
+        None => input,
+    };
+
+    let crlf_lines = text.matches("\r\n").count();
+    if crlf_lines > 0 {
+        changes.push(Change::CrLf { lines: crlf_lines });
+    }
+
+    // `str::lines` already strips the CR of CRLF line endings
+    let mut trailing_whitespace_lines = 0;
+    let mut lines = text
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_end();
+            if trimmed.len() != line.len() {
+                trailing_whitespace_lines += 1;
+            }
+            trimmed
+        })
+        .collect::<Vec<_>>();
+    if trailing_whitespace_lines > 0 {
+        changes.push(Change::TrailingWhitespace {
+            lines: trailing_whitespace_lines,
+        });
+    }
+
+    let leading_blank_lines = lines.iter().take_while(|line| line.is_empty()).count();
+    if leading_blank_lines > 0 {
+        lines.drain(..leading_blank_lines);
+        changes.push(Change::LeadingBlankLines {
+            lines: leading_blank_lines,
+        });
+    }
+    let trailing_blank_lines = lines
+        .iter()
+        .rev()
+        .take_while(|line| line.is_empty())
+        .count();
+    if trailing_blank_lines > 0 {
+        lines.truncate(lines.len() - trailing_blank_lines);
+        changes.push(Change::TrailingBlankLines {
+            lines: trailing_blank_lines,
+        });
+    }
+
+    let indentation = common_indentation(&lines);
+    if !indentation.is_empty() {
+        for line in lines.iter_mut().filter(|line| !line.is_empty()) {
+            *line = &line[indentation.len()..];
+        }
+        changes.push(Change::Dedent {
+            columns: indentation.chars().count(),
+        });
+    }
+
+    for change in &changes {
+        tracing::info!("normalized input: {change}");
+    }
+
+    if changes.is_empty() {
+        return Normalized {
+            text: Cow::Borrowed(input),
+            changes,
+        };
+    }
+
+    let mut normalized = lines.join("\n");
+    if !normalized.is_empty() && text.ends_with('\n') {
+        normalized.push('\n');
+    }
+
+    Normalized {
+        text: Cow::Owned(normalized),
+        changes,
+    }
+}
+
+/// The whitespace all non-blank lines start with.
+fn common_indentation<'a>(lines: &[&'a str]) -> &'a str {
+    let mut non_blank = lines.iter().filter(|line| !line.is_empty());
+    let Some(first) = non_blank.next() else {
+        return "";
+    };
+
+    let mut indentation = &first[..first.len() - first.trim_start().len()];
+    for line in non_blank {
+        let common = indentation
+            .char_indices()
+            .zip(line.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((idx, _), _)| idx)
+            .unwrap_or(indentation.len().min(line.len()));
+        indentation = &indentation[..common];
+    }
+
+    indentation
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_noop() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n3 4 5\n";
+        let normalized = normalize(input);
+
+        assert!(matches!(normalized.text, Cow::Borrowed(_)));
+        assert_eq!(normalized.as_str(), input);
+        assert_eq!(normalized.changes(), []);
+    }
+
+    #[test]
+    fn test_normalize_crlf_and_bom() {
+        let normalized = normalize("\u{feff}LR\r\n\r\nAAA = (BBB, BBB)\r\n");
+
+        assert_eq!(normalized.as_str(), "LR\n\nAAA = (BBB, BBB)\n");
+        assert_eq!(
+            normalized.changes(),
+            [Change::ByteOrderMark, Change::CrLf { lines: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_normalize_indented_fixture() {
+        let normalized = normalize(
+            r#"
+            1abc2
+              pqr3stu8vwx
+            a1b2c3d4e5f
+            "#,
+        );
+
+        assert_eq!(normalized.as_str(), "1abc2\n  pqr3stu8vwx\na1b2c3d4e5f");
+        assert_eq!(
+            normalized.changes(),
+            [
+                Change::TrailingWhitespace { lines: 1 },
+                Change::LeadingBlankLines { lines: 1 },
+                Change::TrailingBlankLines { lines: 1 },
+                Change::Dedent { columns: 12 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_inner_blank_lines() {
+        let normalized = normalize("a \n \nb\n\n\n");
+
+        assert_eq!(normalized.as_str(), "a\n\nb\n");
+        assert_eq!(
+            normalized.changes(),
+            [
+                Change::TrailingWhitespace { lines: 2 },
+                Change::TrailingBlankLines { lines: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_change_display() {
+        assert_eq!(
+            Change::CrLf { lines: 3 }.to_string(),
+            "converted 3 CRLF line endings to LF"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use eyre::{bail, WrapErr};
+use similar::TextDiff;
+
+/// Compares `actual` to the snapshot `<dir>/<name>.snap`, failing with a diff if they differ or
+/// if there's no snapshot yet. With `UPDATE_SNAPSHOTS=1` the snapshot is (re)written instead.
+///
+/// Use [`assert_snapshot!`](crate::assert_snapshot) to keep the snapshots of a crate in its
+/// `snapshots` directory.
+pub fn assert_snapshot(dir: &Path, name: &str, actual: &str) -> eyre::Result<()> {
+    let update = env::var("UPDATE_SNAPSHOTS").is_ok_and(|val| val == "1");
+    check_snapshot(dir, name, actual, update)
+}
+
+fn check_snapshot(dir: &Path, name: &str, actual: &str, update: bool) -> eyre::Result<()> {
+    let path = dir.join(format!("{name}.snap"));
+    let mut actual = actual.to_string();
+    if !actual.ends_with('\n') {
+        actual.push('\n');
+    }
+
+    if update {
+        fs::create_dir_all(dir)?;
+        fs::write(&path, actual)
+            .wrap_err_with(|| format!("couldn't write snapshot {}", path.display()))?;
+        return Ok(());
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => bail!(
+            "snapshot {} doesn't exist yet, run the test with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        ),
+        Err(err) => {
+            return Err(err).wrap_err_with(|| format!("couldn't read snapshot {}", path.display()))
+        }
+    };
+    if expected == actual {
+        return Ok(());
+    }
+
+    let diff = TextDiff::from_lines(&expected, &actual);
+    bail!(
+        "snapshot {} changed, run the test with UPDATE_SNAPSHOTS=1 if that's expected:\n{}",
+        path.display(),
+        diff.unified_diff()
+            .context_radius(3)
+            .header("snapshot", "actual")
+    )
+}
+
+/// Compares a rendering of intermediate results to the snapshot `name` in the `snapshots`
+/// directory of the calling crate, see [`assert_snapshot`](crate::snapshot::assert_snapshot).
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::snapshot::assert_snapshot(
+            ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots")),
+            $name,
+            &$actual,
+        )
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_assert_snapshot() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let err = check_snapshot(dir.path(), "missing", "a", false).unwrap_err();
+        assert!(err.to_string().contains("doesn't exist yet"));
+
+        check_snapshot(dir.path(), "numbers", "1\n2\n3", true)?;
+        assert_eq!(
+            fs::read_to_string(dir.path().join("numbers.snap"))?,
+            "1\n2\n3\n"
+        );
+        check_snapshot(dir.path(), "numbers", "1\n2\n3", false)?;
+
+        let err = check_snapshot(dir.path(), "numbers", "1\n4\n3\n", false).unwrap_err();
+        assert!(err.to_string().ends_with(
+            "\
+--- snapshot
++++ actual
+@@ -1,3 +1,3 @@
+ 1
+-2
++4
+ 3
+"
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use std::env;
+use std::fmt::{self, Write};
+
+use eyre::WrapErr;
+
+use crate::explain::Highlight;
+use crate::PuzzlePart;
+
+/// Renders a picture of how a day solves an input.
+pub type Render = fn(&str, PuzzlePart) -> eyre::Result<Svg>;
+
+/// A minimal SVG document: shapes and text drawn in the order they're added.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    elements: Vec<String>,
+}
+
+/// Presentation attributes of a shape or text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    fill: Option<String>,
+    stroke: Option<String>,
+    stroke_width: Option<f64>,
+    opacity: Option<f64>,
+    font_size: Option<f64>,
+    text_anchor: Option<&'static str>,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fill(mut self, color: impl Into<String>) -> Self {
+        self.fill = Some(color.into());
+        self
+    }
+
+    pub fn stroke(mut self, color: impl Into<String>, width: f64) -> Self {
+        self.stroke = Some(color.into());
+        self.stroke_width = Some(width);
+        self
+    }
+
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = Some(opacity);
+        self
+    }
+
+    pub fn font_size(mut self, size: f64) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Centers text on its position instead of starting it there.
+    pub fn centered(mut self) -> Self {
+        self.text_anchor = Some("middle");
+        self
+    }
+
+    /// Right-aligns text to its position.
+    pub fn end_aligned(mut self) -> Self {
+        self.text_anchor = Some("end");
+        self
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // shapes are filled black by default, which is never what's wanted for outlines
+        write!(
+            f,
+            r#" fill="{}""#,
+            escape(self.fill.as_deref().unwrap_or("none"))
+        )?;
+        if let Some(stroke) = &self.stroke {
+            write!(f, r#" stroke="{}""#, escape(stroke))?;
+        }
+        if let Some(width) = self.stroke_width {
+            write!(f, r#" stroke-width="{width}""#)?;
+        }
+        if let Some(opacity) = self.opacity {
+            write!(f, r#" opacity="{opacity}""#)?;
+        }
+        if let Some(size) = self.font_size {
+            write!(f, r#" font-size="{size}""#)?;
+        }
+        if let Some(anchor) = self.text_anchor {
+            write!(f, r#" text-anchor="{anchor}""#)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The color used for a highlight, so pictures match the explanations.
+pub fn color(highlight: Highlight) -> &'static str {
+    match highlight {
+        Highlight::None => "#333333",
+        Highlight::Focus => "#e6a700",
+        Highlight::Match => "#2e9e44",
+        Highlight::Dim => "#b0b0b0",
+    }
+}
+
+impl Svg {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            elements: vec![],
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: &Style) -> &mut Self {
+        self.push(format!(
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}"{style}/>"#
+        ))
+    }
+
+    pub fn line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), style: &Style) -> &mut Self {
+        self.push(format!(
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"{style}/>"#
+        ))
+    }
+
+    pub fn circle(&mut self, (cx, cy): (f64, f64), r: f64, style: &Style) -> &mut Self {
+        self.push(format!(r#"<circle cx="{cx}" cy="{cy}" r="{r}"{style}/>"#))
+    }
+
+    /// An open line through `points`.
+    pub fn polyline(&mut self, points: &[(f64, f64)], style: &Style) -> &mut Self {
+        self.push(format!(
+            r#"<polyline points="{}"{style}/>"#,
+            points_attr(points)
+        ))
+    }
+
+    /// A closed shape through `points`.
+    pub fn polygon(&mut self, points: &[(f64, f64)], style: &Style) -> &mut Self {
+        self.push(format!(
+            r#"<polygon points="{}"{style}/>"#,
+            points_attr(points)
+        ))
+    }
+
+    /// Text is drawn in the color of [`Highlight::None`] unless the style fills it otherwise.
+    pub fn text(&mut self, (x, y): (f64, f64), text: &str, style: &Style) -> &mut Self {
+        let mut style = style.clone();
+        style
+            .fill
+            .get_or_insert_with(|| color(Highlight::None).to_string());
+        self.push(format!(
+            r#"<text x="{x}" y="{y}"{style}>{}</text>"#,
+            escape(text)
+        ))
+    }
+
+    fn push(&mut self, element: String) -> &mut Self {
+        self.elements.push(element);
+        self
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace">"#,
+            w = self.width,
+            h = self.height
+        )?;
+        writeln!(f, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+        for element in &self.elements {
+            writeln!(f, "{element}")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+fn points_attr(points: &[(f64, f64)]) -> String {
+    points.iter().fold(String::new(), |mut attr, (x, y)| {
+        if !attr.is_empty() {
+            attr.push(' ');
+        }
+        write!(attr, "{x},{y}").expect("writing to a string never fails");
+        attr
+    })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the picture `render` draws of `input` to the file at `SVG`.
+///
+/// Returns `false` if no picture was requested.
+pub fn svg_from_env(render: Render, input: &str, puzzle_part: PuzzlePart) -> eyre::Result<bool> {
+    let Ok(path) = env::var("SVG") else {
+        return Ok(false);
+    };
+
+    let svg = render(input, puzzle_part)?;
+    std::fs::write(&path, svg.to_string()).wrap_err_with(|| format!("couldn't write {path}"))?;
+    println!("Wrote {path}");
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_svg_display() {
+        let mut svg = Svg::new(20.0, 10.0);
+        svg.rect(0.0, 0.0, 5.0, 5.0, &Style::new().fill("red"))
+            .polyline(&[(0.0, 0.0), (1.5, 2.0)], &Style::new().stroke("blue", 0.5))
+            .text(
+                (1.0, 2.0),
+                "a<b & \"c\"",
+                &Style::new().font_size(8.0).centered(),
+            );
+
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10" viewBox="0 0 20 10" font-family="monospace">
+<rect width="100%" height="100%" fill="white"/>
+<rect x="0" y="0" width="5" height="5" fill="red"/>
+<polyline points="0,0 1.5,2" fill="none" stroke="blue" stroke-width="0.5"/>
+<text x="1" y="2" fill="#333333" font-size="8" text-anchor="middle">a&lt;b &amp; &quot;c&quot;</text>
+</svg>
+"##
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::env;
+use std::path::PathBuf;
+
+use eyre::bail;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_error::ErrorLayer;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Filter used when `TRACE` isn't set. It keeps the spans of the solvers' entry points around for
+/// the span traces of error reports without paying for the spans of the inner loops.
+const DEFAULT_FILTER: &str = "info";
+
+/// Where the collected spans and events end up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Human-readable logs on stderr, including the duration of every closed span.
+    Human,
+    /// A Chrome trace JSON file, viewable with `chrome://tracing` or Perfetto.
+    Chrome { path: PathBuf },
+}
+
+impl TraceFormat {
+    /// Parses `human` or `chrome`, the Chrome trace is written to `path`.
+    pub fn parse(name: &str, path: impl Into<PathBuf>) -> eyre::Result<TraceFormat> {
+        let format = if name.eq_ignore_ascii_case("human") {
+            TraceFormat::Human
+        } else if name.eq_ignore_ascii_case("chrome") {
+            TraceFormat::Chrome { path: path.into() }
+        } else {
+            bail!("Unknown trace format: {name}")
+        };
+
+        Ok(format)
+    }
+
+    pub fn from_env() -> eyre::Result<TraceFormat> {
+        match env::var("TRACE_FORMAT") {
+            Ok(val) => Self::parse(&val, trace_file_from_env()),
+            Err(_) => Ok(TraceFormat::Human),
+        }
+    }
+}
+
+/// The file given via `TRACE_FILE` for Chrome traces, `trace.json` by default.
+pub fn trace_file_from_env() -> PathBuf {
+    env::var("TRACE_FILE")
+        .unwrap_or_else(|_| "trace.json".to_string())
+        .into()
+}
+
+/// Keeps the trace output alive; a Chrome trace is only completely written once this is dropped.
+#[must_use]
+pub struct TraceGuard {
+    _chrome: Option<FlushGuard>,
+}
+
+/// Installs the global tracing subscriber.
+///
+/// `TRACE` selects the verbosity using the `EnvFilter` syntax, e.g. `TRACE=debug` or
+/// `TRACE=aoc_day_5=trace`. Without it nothing is printed, but error reports still contain the
+/// span trace of the solver that failed. `TRACE_FORMAT=chrome` writes a Chrome trace to
+/// `TRACE_FILE` (default `trace.json`) instead of logging to stderr.
+pub fn tracing_from_env() -> eyre::Result<TraceGuard> {
+    match env::var("TRACE") {
+        Ok(filter) => install_tracing(Some(&filter), TraceFormat::from_env()?),
+        Err(_) => install_tracing(None, TraceFormat::Human),
+    }
+}
+
+fn install_tracing(filter: Option<&str>, format: TraceFormat) -> eyre::Result<TraceGuard> {
+    let (layer, guard) = trace_layer(filter, format)?;
+    tracing_subscriber::registry().with(layer).try_init()?;
+
+    Ok(guard)
+}
+
+/// The layers [`tracing_from_env`] installs, boxed so they can be put next to other layers, e.g.
+/// in a scoped subscriber.
+pub type TraceLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Same as [`tracing_from_env`], but with the filter and format given directly, e.g. from command
+/// line flags. The filter only applies to the returned layer and not to layers next to it.
+pub fn trace_layer(
+    filter: Option<&str>,
+    format: TraceFormat,
+) -> eyre::Result<(TraceLayer, TraceGuard)> {
+    let Some(filter) = filter else {
+        let layer = ErrorLayer::default().with_filter(EnvFilter::new(DEFAULT_FILTER));
+        return Ok((Box::new(layer), TraceGuard { _chrome: None }));
+    };
+    let filter = EnvFilter::try_new(filter)?;
+
+    let traced = match format {
+        TraceFormat::Human => {
+            let layer = ErrorLayer::default()
+                .and_then(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(std::io::stderr)
+                        .with_span_events(FmtSpan::CLOSE),
+                )
+                .with_filter(filter);
+
+            (Box::new(layer) as TraceLayer, TraceGuard { _chrome: None })
+        }
+        TraceFormat::Chrome { path } => {
+            let (chrome_layer, guard) = ChromeLayerBuilder::new()
+                .file(path)
+                .include_args(true)
+                .build();
+            let layer = ErrorLayer::default()
+                .and_then(chrome_layer)
+                .with_filter(filter);
+
+            (
+                Box::new(layer) as TraceLayer,
+                TraceGuard {
+                    _chrome: Some(guard),
+                },
+            )
+        }
+    };
+
+    Ok(traced)
+}
@@ -0,0 +1,51 @@
+use aoc_2023_common::extract::Extraction;
+use aoc_2023_common::PuzzlePart;
+use aoc_day_1::parallel::{calibrate_parallel, generate_document};
+use aoc_day_1::{calibrate, calibration_value, calibration_value_by_match_indices, Scanner};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+type CalibrationValue = fn(&str, PuzzlePart) -> Option<u32>;
+
+fn bench_calibration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calibration");
+    for size in [32_000, 3_200_000] {
+        let document = String::from_utf8(generate_document(size)).expect("documents are ASCII");
+        group.throughput(Throughput::Bytes(document.len() as u64));
+
+        let implementations: [(&str, CalibrationValue); 2] = [
+            ("automaton", calibration_value),
+            ("match indices", calibration_value_by_match_indices),
+        ];
+        for (name, calibration_value) in implementations {
+            group.bench_with_input(BenchmarkId::new(name, size), &document, |b, document| {
+                b.iter(|| {
+                    document
+                        .lines()
+                        .filter_map(|line| calibration_value(line, PuzzlePart::Two))
+                        .sum::<u32>()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel");
+    group.sample_size(10);
+    let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+    let extraction = Extraction::default();
+
+    let document = generate_document(256_000_000);
+    group.throughput(Throughput::Bytes(document.len() as u64));
+    group.bench_function("sequential", |b| {
+        b.iter(|| calibrate(&document[..], scanner, &extraction))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| calibrate_parallel(&document, scanner, &extraction))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_calibration, bench_parallel);
+criterion_main!(benches);
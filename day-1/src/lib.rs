@@ -0,0 +1,824 @@
+use aoc_2023_common::differential::Solutions;
+use aoc_2023_common::explain::{Highlight, Span, Step};
+use aoc_2023_common::extract::Extraction;
+use aoc_2023_common::input::try_fold_lines;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::PuzzlePart;
+use std::cmp::Reverse;
+use std::env;
+use std::fmt;
+use std::io::BufRead;
+use std::iter;
+use std::ops::Range;
+use std::str::FromStr;
+
+use aho_corasick::{AhoCorasick, Anchored, Input, MatchKind, StartKind};
+use eyre::{bail, ContextCompat, WrapErr};
+use once_cell::sync::Lazy;
+use tracing::instrument;
+
+use crate::vocabulary::Vocabulary;
+
+pub mod parallel;
+pub mod vocabulary;
+
+static VOCABULARIES: Lazy<[Vocabulary; 2]> =
+    Lazy::new(|| [PuzzlePart::One, PuzzlePart::Two].map(Vocabulary::for_puzzle_part));
+static SCANNERS: Lazy<[Scanner; 2]> = Lazy::new(|| VOCABULARIES.each_ref().map(Scanner::new));
+
+/// Finds the first and last digit of a line, written as a digit or spelled out, with one
+/// automaton: a search from the start for the first digit and anchored searches at every position
+/// from the end for the last one, so neither needs to look at the rest of the line.
+///
+/// Where words overlap, like in `eightwo`, each counts: the first digit is the word starting first
+/// and the last digit the word starting last, the longer word if several start at the same place.
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    automaton: AhoCorasick,
+    /// The number of every pattern, by pattern ID.
+    digits: Vec<u32>,
+    /// The patterns for the digits themselves come first, the words after them.
+    digit_patterns: usize,
+}
+
+impl Scanner {
+    /// A scanner for the digits `0` to `9` and the words of `vocabulary`.
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        Self::with_radix(vocabulary, 10)
+    }
+
+    /// A scanner for the digits of base `radix`, e.g. `0` to `9` and `a` to `f` as well as `A` to
+    /// `F` for base 16, and the words of `vocabulary`.
+    pub fn with_radix(vocabulary: &Vocabulary, radix: u32) -> Self {
+        let digits = (0..radix)
+            .filter_map(|digit| char::from_digit(digit, radix).map(|c| (c, digit)))
+            .flat_map(|(c, digit)| {
+                let upper = Some(c.to_ascii_uppercase()).filter(|&upper| upper != c);
+                iter::once(c)
+                    .chain(upper)
+                    .map(move |c| (c.to_string(), digit))
+            })
+            .collect::<Vec<_>>();
+        let digit_patterns = digits.len();
+        let (patterns, digits): (Vec<_>, Vec<_>) = digits
+            .into_iter()
+            .chain(
+                vocabulary
+                    .words()
+                    .map(|(word, number)| (word.to_string(), number)),
+            )
+            .unzip();
+        let automaton = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .start_kind(StartKind::Both)
+            .build(patterns)
+            .expect("vocabularies are small enough for an automaton");
+
+        Self {
+            automaton,
+            digits,
+            digit_patterns,
+        }
+    }
+
+    /// The scanner for a puzzle part's vocabulary.
+    pub fn for_puzzle_part(puzzle_part: PuzzlePart) -> &'static Self {
+        match puzzle_part {
+            PuzzlePart::One => &SCANNERS[0],
+            PuzzlePart::Two => &SCANNERS[1],
+        }
+    }
+
+    /// The first and last digit of `line`, which are the same if there's only one.
+    pub fn first_and_last(&self, line: &(impl AsRef<[u8]> + ?Sized)) -> Option<(u32, u32)> {
+        let (first, last) = self.find_first_and_last(line)?;
+
+        Some((first.number, last.number))
+    }
+
+    /// Same as [`Scanner::first_and_last`], but with where the digits are and how they're written.
+    pub fn find_first_and_last(
+        &self,
+        line: &(impl AsRef<[u8]> + ?Sized),
+    ) -> Option<(Found, Found)> {
+        let first = self.find_iter(line).next()?;
+        let last = self
+            .rfind_iter(line)
+            .next()
+            .expect("the first digit is found at the latest");
+
+        Some((first, last))
+    }
+
+    /// All digits of `line` in order, one for every place a digit or word starts at. Lines can be
+    /// given as bytes, so documents don't have to be valid UTF-8 to be scanned.
+    pub fn find_iter<'a>(
+        &'a self,
+        line: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> impl Iterator<Item = Found> + 'a {
+        let line = line.as_ref();
+        let mut start = 0;
+        iter::from_fn(move || {
+            let found = self.automaton.find(Input::new(line).range(start..))?;
+            start = found.start() + 1;
+            Some(self.found(found))
+        })
+    }
+
+    /// Same as [`Scanner::find_iter`], but from the end of `line` to its start.
+    pub fn rfind_iter<'a>(
+        &'a self,
+        line: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> impl Iterator<Item = Found> + 'a {
+        let line = line.as_ref();
+        let mut end = line.len();
+        iter::from_fn(move || {
+            let found = (0..end).rev().find_map(|start| {
+                let input = Input::new(line).range(start..).anchored(Anchored::Yes);
+                self.automaton.find(input)
+            })?;
+            end = found.start();
+            Some(self.found(found))
+        })
+    }
+
+    fn found(&self, found: aho_corasick::Match) -> Found {
+        let pattern = found.pattern().as_usize();
+        Found {
+            range: found.range(),
+            number: self.digits[pattern],
+            source: if pattern < self.digit_patterns {
+                Source::Digit
+            } else {
+                Source::Word
+            },
+        }
+    }
+}
+
+/// How a digit is written in a line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Digit,
+    /// Spelled out with a word of the vocabulary.
+    Word,
+}
+
+/// A digit in a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Found {
+    /// The byte range of the digit or word in the line.
+    pub range: Range<usize>,
+    pub number: u32,
+    pub source: Source,
+}
+
+/// Which digits were picked from a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineExplanation {
+    /// 1-based.
+    pub line_number: usize,
+    pub line: String,
+    /// The first and last digit, `None` if the line has no digits.
+    pub digits: Option<(Found, Found)>,
+}
+
+impl LineExplanation {
+    pub fn value(&self) -> Option<u32> {
+        let (first, last) = self.digits.as_ref()?;
+
+        Some(concat(first.number, last.number))
+    }
+
+    /// The line with the first digit focused and the last one matched. Where the two overlap, the
+    /// shared part belongs to the first one.
+    pub fn spans(&self) -> Vec<Span> {
+        let Some((first, last)) = &self.digits else {
+            return vec![Span::highlighted(&self.line, Highlight::Dim)];
+        };
+
+        let last_start = last.range.start.max(first.range.end);
+        let mut spans = vec![
+            Span::plain(&self.line[..first.range.start]),
+            Span::highlighted(&self.line[first.range.clone()], Highlight::Focus),
+        ];
+        if last_start < last.range.end {
+            spans.push(Span::plain(&self.line[first.range.end..last_start]));
+            spans.push(Span::highlighted(
+                &self.line[last_start..last.range.end],
+                Highlight::Match,
+            ));
+        }
+        spans.push(Span::plain(
+            &self.line[last.range.end.max(first.range.end)..],
+        ));
+        spans.retain(|span| !span.text.is_empty());
+
+        spans
+    }
+}
+
+/// Finds the digits picked from every line of the document in `reader`.
+pub fn explain_lines(
+    reader: impl BufRead,
+    scanner: &Scanner,
+) -> eyre::Result<Vec<LineExplanation>> {
+    let mut line_number = 0;
+    try_fold_lines(reader, vec![], |mut explanations, line| {
+        line_number += 1;
+        explanations.push(LineExplanation {
+            line_number,
+            line: line.to_string(),
+            digits: scanner.find_first_and_last(line),
+        });
+
+        Ok(explanations)
+    })
+}
+
+/// One step per line showing the digits picked from it, then the sum.
+pub fn explain(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Vec<Step>> {
+    let explanations = explain_lines(
+        normalize(input).as_bytes(),
+        Scanner::for_puzzle_part(puzzle_part),
+    )?;
+    let describe = |found: &Found| match found.source {
+        Source::Digit => format!("the digit {}", found.number),
+        Source::Word => format!("the word for {}", found.number),
+    };
+
+    let mut steps = explanations
+        .iter()
+        .map(|explanation| {
+            let caption = match (&explanation.digits, explanation.value()) {
+                (Some((first, last)), Some(value)) => format!(
+                    "line {}: {} and {} make {value}",
+                    explanation.line_number,
+                    describe(first),
+                    describe(last)
+                ),
+                _ => format!("line {}: no digits, skipped", explanation.line_number),
+            };
+            Step::new(caption).line(explanation.spans())
+        })
+        .collect::<Vec<_>>();
+    let sum = explanations
+        .iter()
+        .filter_map(LineExplanation::value)
+        .sum::<u32>();
+    steps.push(Step::new(format!("the calibration values sum up to {sum}")));
+
+    Ok(steps)
+}
+
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> u32 {
+    calculate_result_with(input, puzzle_part, calibration_value)
+}
+
+/// Both ways of finding the digits of a line, so the simple one can check the automaton.
+pub fn solutions() -> Solutions<u32> {
+    Solutions::new()
+        .register(PuzzlePart::One, "automaton", |input| {
+            Ok(calculate_result(input, PuzzlePart::One))
+        })
+        .register(PuzzlePart::One, "match indices", |input| {
+            Ok(calculate_result_with(
+                input,
+                PuzzlePart::One,
+                calibration_value_by_match_indices,
+            ))
+        })
+        .register(PuzzlePart::Two, "automaton", |input| {
+            Ok(calculate_result(input, PuzzlePart::Two))
+        })
+        .register(PuzzlePart::Two, "match indices", |input| {
+            Ok(calculate_result_with(
+                input,
+                PuzzlePart::Two,
+                calibration_value_by_match_indices,
+            ))
+        })
+}
+
+#[instrument(skip(input, calibration_value))]
+fn calculate_result_with(
+    input: &str,
+    puzzle_part: PuzzlePart,
+    calibration_value: fn(&str, PuzzlePart) -> Option<u32>,
+) -> u32 {
+    try_fold_lines(normalize(input).as_bytes(), 0, |sum, line| {
+        Ok(sum + calibration_value(line, puzzle_part).unwrap_or(0))
+    })
+    .expect("reading from a string never fails")
+}
+
+/// Same as [`calculate_result`], but reads the input line by line.
+pub fn calculate_result_from_reader(
+    reader: impl BufRead,
+    puzzle_part: PuzzlePart,
+) -> eyre::Result<u32> {
+    let sum = calculate_result_with_scanner(reader, Scanner::for_puzzle_part(puzzle_part))?;
+
+    u32::try_from(sum).wrap_err("sum of the calibration values too large")
+}
+
+/// Same as [`calculate_result_from_reader`], but with the words `scanner` recognizes instead of
+/// those of a puzzle part.
+pub fn calculate_result_with_scanner(reader: impl BufRead, scanner: &Scanner) -> eyre::Result<u64> {
+    Ok(calibrate(reader, scanner, &Extraction::default())?.sum)
+}
+
+/// How lines without any digits are dealt with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Lines without digits are an error.
+    Strict,
+    /// Lines without digits are skipped, but reported.
+    Lenient,
+}
+
+impl FromStr for Mode {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            _ => bail!("unknown mode: {s}, expected strict or lenient"),
+        }
+    }
+}
+
+impl Mode {
+    /// The mode given via the `CALIBRATION` env var. Returns `None` if it isn't set, in which case
+    /// lines without digits are skipped silently like the puzzle expects.
+    pub fn from_env() -> eyre::Result<Option<Mode>> {
+        match env::var("CALIBRATION") {
+            Ok(val) => Ok(Some(val.parse()?)),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// A line without any digits, which has no calibration value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedLine {
+    /// 1-based.
+    pub line_number: usize,
+    pub content: String,
+}
+
+impl fmt::Display for SkippedLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {:?}", self.line_number, self.content)
+    }
+}
+
+/// The sum of the calibration values of a document and the lines it had to skip.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: u64,
+    pub skipped: Vec<SkippedLine>,
+}
+
+impl Calibration {
+    /// The sum, or an error listing every skipped line if there are any.
+    pub fn strict(self) -> eyre::Result<u64> {
+        if self.skipped.is_empty() {
+            return Ok(self.sum);
+        }
+
+        let lines = self
+            .skipped
+            .iter()
+            .map(|skipped| format!("\n  {skipped}"))
+            .collect::<String>();
+        match self.skipped.len() {
+            1 => bail!("1 line has no digits:{lines}"),
+            count => bail!("{count} lines have no digits:{lines}"),
+        }
+    }
+
+    /// Applies `mode`, keeping the skipped lines if it's lenient.
+    pub fn with_mode(self, mode: Mode) -> eyre::Result<Self> {
+        match mode {
+            Mode::Strict => Ok(Self {
+                sum: self.strict()?,
+                skipped: vec![],
+            }),
+            Mode::Lenient => Ok(self),
+        }
+    }
+}
+
+/// Sums the values `extraction` makes of the digits of every line of the document in `reader`,
+/// keeping track of the lines without a value.
+#[instrument(name = "calculate_result", skip_all)]
+pub fn calibrate(
+    reader: impl BufRead,
+    scanner: &Scanner,
+    extraction: &Extraction,
+) -> eyre::Result<Calibration> {
+    let mut line_number = 0;
+    try_fold_lines(reader, Calibration::default(), |mut calibration, line| {
+        line_number += 1;
+        match extract(line, scanner, extraction)? {
+            Some(value) => {
+                calibration.sum = calibration
+                    .sum
+                    .checked_add(value)
+                    .wrap_err("sum of the calibration values too large")?;
+            }
+            None => calibration.skipped.push(SkippedLine {
+                line_number,
+                content: line.to_string(),
+            }),
+        }
+
+        Ok(calibration)
+    })
+}
+
+/// The number formed by the first and last digit of `line`, if it contains any digits.
+pub fn calibration_value(line: &str, puzzle_part: PuzzlePart) -> Option<u32> {
+    calibration_value_with_scanner(line, Scanner::for_puzzle_part(puzzle_part))
+}
+
+/// The value `extraction` makes of the digits of `line`, only looking for as many digits as it
+/// needs.
+pub fn extract(
+    line: &(impl AsRef<[u8]> + ?Sized),
+    scanner: &Scanner,
+    extraction: &Extraction,
+) -> eyre::Result<Option<u64>> {
+    let numbers = |found: &mut dyn Iterator<Item = Found>| {
+        found.map(|found| found.number).collect::<Vec<_>>()
+    };
+
+    match extraction.ends() {
+        Some((first, last)) => {
+            let first = numbers(&mut scanner.find_iter(line).take(first));
+            let mut last = numbers(&mut scanner.rfind_iter(line).take(last));
+            last.reverse();
+            extraction.extract_ends(&first, &last)
+        }
+        None => extraction.extract(&numbers(&mut scanner.find_iter(line))),
+    }
+}
+
+pub fn calibration_value_with_scanner(
+    line: &(impl AsRef<[u8]> + ?Sized),
+    scanner: &Scanner,
+) -> Option<u32> {
+    let (first, last) = scanner.first_and_last(line)?;
+
+    Some(concat(first, last))
+}
+
+/// Same as [`calibration_value`], but finds every digit and every word and picks the first and
+/// last by position.
+pub fn calibration_value_by_match_indices(line: &str, puzzle_part: PuzzlePart) -> Option<u32> {
+    let vocabulary = match puzzle_part {
+        PuzzlePart::One => &VOCABULARIES[0],
+        PuzzlePart::Two => &VOCABULARIES[1],
+    };
+    let (first, last) = first_and_last_by_match_indices(line, vocabulary)?;
+
+    Some(concat(first, last))
+}
+
+fn first_and_last_by_match_indices(line: &str, vocabulary: &Vocabulary) -> Option<(u32, u32)> {
+    // (index, length, number) of every occurrence
+    let digits = line
+        .char_indices()
+        .filter_map(|(idx, c)| Some((idx, 1, c.to_digit(10)?)));
+    let words = vocabulary.words().flat_map(|(word, number)| {
+        line.match_indices(word)
+            .map(move |(idx, _)| (idx, word.len(), number))
+    });
+    let occurrences = digits.chain(words).collect::<Vec<_>>();
+
+    let first = occurrences
+        .iter()
+        .min_by_key(|&&(idx, len, _)| (idx, Reverse(len)))?;
+    let last = occurrences
+        .iter()
+        .max_by_key(|&&(idx, len, _)| (idx, len))?;
+
+    Some((first.2, last.2))
+}
+
+/// Writes `last` after `first`, which only differs from `first * 10 + last` for numbers above 9.
+fn concat(first: u32, last: u32) -> u32 {
+    checked_concat(first, last).expect("vocabularies only have numbers that fit when concatenated")
+}
+
+/// Same as [`concat`], but `None` if the result doesn't fit into a `u32`.
+pub(crate) fn checked_concat(first: u32, last: u32) -> Option<u32> {
+    let last_digits = last.checked_ilog10().unwrap_or(0) + 1;
+
+    first
+        .checked_mul(10u32.checked_pow(last_digits)?)?
+        .checked_add(last)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::extract::Rule;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() {
+        let res = calculate_result(
+            r#"
+            1abc2
+            pqr3stu8vwx
+            a1b2c3d4e5f
+            treb7uchet
+            "#,
+            PuzzlePart::One,
+        );
+        assert_eq!(res, 142);
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() {
+        let res = calculate_result(
+            r#"
+            two1nine
+            eightwothree
+            abcone2threexyz
+            xtwone3four
+            4nineeightseven2
+            zoneight234
+            7pqrstsixteen
+            "#,
+            PuzzlePart::Two,
+        );
+        assert_eq!(res, 281);
+    }
+
+    #[test]
+    fn test_scanner_overlapping_words() {
+        let scanner = Scanner::new(&Vocabulary::english());
+        assert_eq!(scanner.first_and_last("eightwo"), Some((8, 2)));
+        assert_eq!(scanner.first_and_last("zoneight"), Some((1, 8)));
+        assert_eq!(scanner.first_and_last("xx7xx"), Some((7, 7)));
+        assert_eq!(scanner.first_and_last("twone"), Some((2, 1)));
+        assert_eq!(scanner.first_and_last("abc"), None);
+
+        let scanner = Scanner::new(&Vocabulary::new());
+        assert_eq!(scanner.first_and_last("one2three0"), Some((2, 0)));
+    }
+
+    #[test]
+    fn test_scanner_vocabularies() -> eyre::Result<()> {
+        let scanner =
+            |name| -> eyre::Result<Scanner> { Ok(Scanner::new(&Vocabulary::builtin(name)?)) };
+
+        let german = scanner("german")?;
+        assert_eq!(german.first_and_last("xsiebeneunx"), Some((7, 9)));
+        assert_eq!(german.first_and_last("fünfzig"), Some((5, 5)));
+
+        let french = scanner("french")?;
+        assert_eq!(french.first_and_last("huitrois"), Some((8, 3)));
+
+        let ordinals = scanner("ordinals")?;
+        assert_eq!(ordinals.first_and_last("the secondfifthird"), Some((2, 3)));
+
+        let english = Vocabulary::english().merge(Vocabulary::builtin("zero-ten")?)?;
+        let scanner = Scanner::new(&english);
+        assert_eq!(
+            calibration_value_with_scanner("tenine", &scanner),
+            Some(109)
+        );
+        assert_eq!(calibration_value_with_scanner("zerone", &scanner), Some(1));
+        assert_eq!(
+            calibration_value_with_scanner("2often", &scanner),
+            Some(210)
+        );
+
+        // a longer word starting at the same place wins
+        let scanner = Scanner::new(&Vocabulary::english().merge(Vocabulary::builtin("ordinals")?)?);
+        assert_eq!(scanner.first_and_last("ninth"), Some((9, 9)));
+        assert_eq!(scanner.first_and_last("eighthree"), Some((8, 3)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solutions_agree_on_puzzle_input() {
+        let input = include_str!("input.txt");
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            let disagreement = solutions().check::<String>(puzzle_part, input);
+            assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_calculate_result_from_reader() -> eyre::Result<()> {
+        let input = "two1nine\neightwothree\r\nabcone2threexyz\nxtwone3four\n4nineeightseven2\n";
+        // a tiny buffer makes lines span several reads
+        let reader = BufReader::with_capacity(4, input.as_bytes());
+        let res = calculate_result_from_reader(reader, PuzzlePart::Two)?;
+        assert_eq!(res, calculate_result(input, PuzzlePart::Two));
+        assert_eq!(res, 29 + 83 + 13 + 24 + 42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibrate_modes() -> eyre::Result<()> {
+        let input = "1abc2\nabc\ntreb7uchet\n\nxyz\n";
+        let scanner = Scanner::for_puzzle_part(PuzzlePart::One);
+
+        let calibration = calibrate(input.as_bytes(), scanner, &Extraction::default())?;
+        assert_eq!(calibration.sum, 12 + 77);
+        assert_eq!(
+            calibration.skipped,
+            [(2, "abc"), (4, ""), (5, "xyz")].map(|(line_number, content)| SkippedLine {
+                line_number,
+                content: content.to_string()
+            })
+        );
+        assert_eq!(calibration.clone().with_mode(Mode::Lenient)?, calibration);
+
+        let err = calibration.with_mode(Mode::Strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "\
+3 lines have no digits:
+  line 2: \"abc\"
+  line 4: \"\"
+  line 5: \"xyz\""
+        );
+
+        let calibration = calibrate(
+            "two1nine\nthree".as_bytes(),
+            scanner,
+            &Extraction::default(),
+        )?;
+        assert_eq!(
+            calibration.strict().unwrap_err().to_string(),
+            "1 line has no digits:\n  line 2: \"three\""
+        );
+        let calibration = calibrate(
+            "two1nine\nthree".as_bytes(),
+            Scanner::for_puzzle_part(PuzzlePart::Two),
+            &Extraction::default(),
+        )?;
+        assert_eq!(calibration.strict()?, 29 + 33);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_rules() -> eyre::Result<()> {
+        let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+        let by_rule = |line, rule: &str| -> eyre::Result<Option<u64>> {
+            extract(line, scanner, &Extraction::new(rule.parse()?))
+        };
+
+        assert_eq!(by_rule("a1eightwo3four", "first-last")?, Some(14));
+        assert_eq!(by_rule("a1eightwo3four", "first-2")?, Some(18));
+        assert_eq!(by_rule("a1eightwo3four", "last-3")?, Some(234));
+        assert_eq!(by_rule("a1eightwo3four", "first-1-last-2")?, Some(134));
+        assert_eq!(by_rule("a1eightwo3four", "all")?, Some(18234));
+        assert_eq!(by_rule("abc", "all")?, None);
+
+        let sum = Extraction::new(Rule::Custom(Arc::new(|digits, _| {
+            Some(digits.iter().map(|&digit| u64::from(digit)).sum())
+        })));
+        assert_eq!(extract("a1eightwo3four", scanner, &sum)?, Some(18));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_hex() -> eyre::Result<()> {
+        let vocabulary = Vocabulary::english().merge(Vocabulary::builtin("hex")?)?;
+        let scanner = Scanner::with_radix(&vocabulary, 16);
+        let hex = Extraction::default().radix(16)?;
+
+        assert_eq!(extract("xyz0Fgh", &scanner, &hex)?, Some(0x0f));
+        assert_eq!(extract("twelvexx3", &scanner, &hex)?, Some(0xc3));
+        // words win over the hex digits they start with
+        assert_eq!(extract("eight", &scanner, &hex)?, Some(0x88));
+        assert_eq!(
+            scanner
+                .find_iter("elevenf")
+                .map(|found| (found.number, found.source))
+                .collect::<Vec<_>>(),
+            [
+                (11, Source::Word),
+                (14, Source::Digit),
+                (14, Source::Digit),
+                (15, Source::Digit)
+            ]
+        );
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_find_iter_matches_rfind_iter(line in "(one|two|eight|nine|[0-9]|[a-zé]){0,12}") {
+            let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+            let mut backwards = scanner.rfind_iter(&line).collect::<Vec<_>>();
+            backwards.reverse();
+
+            prop_assert_eq!(scanner.find_iter(&line).collect::<Vec<_>>(), backwards);
+        }
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let explanations = explain_lines(
+            "xtwone3four\neightwo\nabc\n7".as_bytes(),
+            Scanner::for_puzzle_part(PuzzlePart::Two),
+        )?;
+        assert_eq!(
+            explanations[0].digits,
+            Some((
+                Found {
+                    range: 1..4,
+                    number: 2,
+                    source: Source::Word
+                },
+                Found {
+                    range: 7..11,
+                    number: 4,
+                    source: Source::Word
+                }
+            ))
+        );
+        assert_eq!(explanations[3].value(), Some(77));
+
+        let steps = explain("xtwone3four\neightwo\nabc\n7", PuzzlePart::Two)?;
+        let steps = steps.iter().map(Step::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            [
+                "line 1: the word for 2 and the word for 4 make 24\nx[two]ne3<four>",
+                "line 2: the word for 8 and the word for 2 make 82\n[eight]<wo>",
+                "line 3: no digits, skipped\nabc",
+                "line 4: the digit 7 and the digit 7 make 77\n[7]",
+                "the calibration values sum up to 183",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<String>(
+            "\
+1abc2
+two1nine
+abcone2threexyz
+7pqrstsixteen",
+            |input| {
+                calculate_result(input, PuzzlePart::One) != calculate_result(input, PuzzlePart::Two)
+            },
+        )?;
+        assert_eq!(res, "two1nine");
+
+        Ok(())
+    }
+
+    fn all_builtin_vocabularies() -> Vocabulary {
+        vocabulary::BUILTIN
+            .iter()
+            .try_fold(Vocabulary::new(), |vocabulary, (name, _)| {
+                vocabulary.merge(Vocabulary::builtin(name)?)
+            })
+            .expect("the built-in vocabularies don't contradict each other")
+    }
+
+    proptest! {
+        #[test]
+        fn test_solutions_agree(lines in prop::collection::vec("(one|two|eight|nine|[0-9]|[a-zé]){0,12}", 1..8)) {
+            let input = lines.join("\n");
+            for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+                let disagreement = solutions().check::<String>(puzzle_part, &input);
+                prop_assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+            }
+        }
+
+        #[test]
+        fn test_scanner_matches_match_indices(line in "(eight|eighth|ten|un|deux|huit|trois|sechs|sieben|neun|[0-9]|[a-zé]){0,10}") {
+            let vocabulary = all_builtin_vocabularies();
+            let scanner = Scanner::new(&vocabulary);
+
+            prop_assert_eq!(
+                scanner.first_and_last(&line),
+                first_and_last_by_match_indices(&line, &vocabulary)
+            );
+        }
+    }
+}
@@ -1,91 +1,63 @@
-#![feature(iter_collect_into)]
+use aoc_2023_common::differential::differential_from_env;
+use aoc_2023_common::explain::explain_from_env;
+use aoc_2023_common::extract::Extraction;
+use aoc_2023_common::init;
+use aoc_2023_common::input::input_from_env;
+use aoc_2023_common::minimize::minimize_from_env;
+use aoc_2023_common::trace::tracing_from_env;
+use aoc_day_1::parallel::{calibrate_parallel, throughput_from_env};
+use aoc_day_1::vocabulary::Vocabulary;
+use aoc_day_1::{calculate_result, calibrate, explain, solutions, Mode, Scanner};
+
+use std::env;
 
-use aoc_2023_common::{init, PuzzlePart};
-
-const WORD_TO_DIGIT: [(&str, u32); 9] = [
-    ("one", 1),
-    ("two", 2),
-    ("three", 3),
-    ("four", 4),
-    ("five", 5),
-    ("six", 6),
-    ("seven", 7),
-    ("eight", 8),
-    ("nine", 9),
-];
 const INPUT: &str = include_str!("input.txt");
 
 fn main() -> eyre::Result<()> {
     let puzzle_part = init()?;
-    let sum = calculate_result(INPUT, puzzle_part);
-    println!("The result for puzzle part '{puzzle_part:?}' is: {sum}");
-
-    Ok(())
-}
-
-fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> u32 {
-    input
-        .lines()
-        .filter_map(|line| {
-            let mut index_to_digit = Vec::new();
-
-            line.chars()
-                .enumerate()
-                .filter_map(|(idx, c)| Some((idx, c.to_digit(10)?)))
-                .collect_into(&mut index_to_digit);
-
-            if puzzle_part == PuzzlePart::Two {
-                WORD_TO_DIGIT
-                    .iter()
-                    .flat_map(|(word, digit)| {
-                        line.match_indices(word).map(|(idx, _)| (idx, *digit))
-                    })
-                    .collect_into(&mut index_to_digit);
-            }
-
-            index_to_digit.sort_unstable_by_key(|(idx, _)| *idx);
-
-            let first_digit = index_to_digit.first().map(|(_, digit)| *digit)?;
-            let second_digit = index_to_digit.last().map(|(_, digit)| *digit)?;
-
-            Some((first_digit, second_digit))
-        })
-        .map(|(first_digit, last_digit)| first_digit * 10 + last_digit)
-        .sum()
-}
+    let _tracing = tracing_from_env()?;
+    if minimize_from_env::<String, _>(|input| Ok(calculate_result(input, puzzle_part)))? {
+        return Ok(());
+    }
+    if differential_from_env::<String, _>(&solutions(), INPUT, puzzle_part)? {
+        return Ok(());
+    }
+    if explain_from_env(explain, INPUT, puzzle_part)? {
+        return Ok(());
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    let mut reader = input_from_env()?.unwrap_or_else(|| Box::new(INPUT.as_bytes()));
+    let extraction = Extraction::from_env()?;
+    let scanner = match (Vocabulary::from_env()?, extraction.radix) {
+        (None, 10) => Scanner::for_puzzle_part(puzzle_part),
+        (vocabulary, radix) => {
+            let vocabulary = vocabulary.unwrap_or_else(|| Vocabulary::for_puzzle_part(puzzle_part));
+            &Scanner::with_radix(&vocabulary, radix)
+        }
+    };
+    if throughput_from_env(scanner, &extraction)? {
+        return Ok(());
+    }
 
-    #[test]
-    fn test_calculate_result_puzzle_part_1() {
-        let res = calculate_result(
-            r#"
-            1abc2
-            pqr3stu8vwx
-            a1b2c3d4e5f
-            treb7uchet
-            "#,
-            PuzzlePart::One,
-        );
-        assert_eq!(res, 142);
+    let mut calibration = if env::var("PARALLEL").is_ok_and(|val| val == "1") {
+        let mut document = vec![];
+        reader.read_to_end(&mut document)?;
+        calibrate_parallel(&document, scanner, &extraction)?
+    } else {
+        calibrate(reader, scanner, &extraction)?
+    };
+    match Mode::from_env()? {
+        Some(mode) => calibration = calibration.with_mode(mode)?,
+        None => calibration.skipped.clear(),
     }
 
-    #[test]
-    fn test_calculate_result_puzzle_part_2() {
-        let res = calculate_result(
-            r#"
-            two1nine
-            eightwothree
-            abcone2threexyz
-            xtwone3four
-            4nineeightseven2
-            zoneight234
-            7pqrstsixteen
-            "#,
-            PuzzlePart::Two,
-        );
-        assert_eq!(res, 281);
+    println!(
+        "The result for puzzle part '{puzzle_part:?}' is: {}",
+        calibration.sum
+    );
+    for skipped in &calibration.skipped {
+        println!("Skipped {skipped}");
     }
+
+    Ok(())
 }
@@ -0,0 +1,335 @@
+use std::env;
+use std::iter;
+use std::time::Instant;
+
+use aoc_2023_common::extract::Extraction;
+use eyre::{bail, ContextCompat, WrapErr};
+use rayon::prelude::*;
+use tracing::instrument;
+
+use crate::{extract, Calibration, Scanner, SkippedLine};
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+/// Chunks smaller than this aren't worth handing to another thread.
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+/// Generated documents are generated in blocks of this size, each on its own.
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Same as [`calibrate`](crate::calibrate), but for a document that's already in memory, which is
+/// split into chunks at line boundaries that are calibrated on all threads of rayon's pool.
+///
+/// Lines are found with `memchr` and scanned as bytes, so both only ever look at the bytes they
+/// need to with SIMD where the CPU supports it. Unlike [`calibrate`](crate::calibrate), invalid
+/// UTF-8 doesn't fail, but is replaced in the skipped lines.
+pub fn calibrate_parallel(
+    document: &[u8],
+    scanner: &Scanner,
+    extraction: &Extraction,
+) -> eyre::Result<Calibration> {
+    let chunk_size = (document.len() / (rayon::current_num_threads() * 4)).max(MIN_CHUNK_SIZE);
+
+    calibrate_chunked(document, scanner, extraction, chunk_size)
+}
+
+/// Same as [`calibrate_parallel`], with chunks of about `chunk_size` bytes.
+#[instrument(name = "calculate_result", skip(document, scanner, extraction))]
+pub fn calibrate_chunked(
+    document: &[u8],
+    scanner: &Scanner,
+    extraction: &Extraction,
+    chunk_size: usize,
+) -> eyre::Result<Calibration> {
+    let document = document.strip_prefix(BYTE_ORDER_MARK).unwrap_or(document);
+    let results = chunks(document, chunk_size)
+        .par_iter()
+        .map(|chunk| calibrate_chunk(chunk, scanner, extraction))
+        .collect::<Vec<_>>();
+
+    // the line numbers of the chunks start at 1, they're only known once the lines of all chunks
+    // before them are counted
+    let mut calibration = Calibration::default();
+    let mut lines_before = 0;
+    for result in results {
+        let (chunk, lines) = result
+            .map_err(|(line, err)| err.wrap_err(format!("in line {}", lines_before + line)))?;
+        calibration.sum = calibration
+            .sum
+            .checked_add(chunk.sum)
+            .wrap_err("sum of the calibration values too large")?;
+        calibration
+            .skipped
+            .extend(chunk.skipped.into_iter().map(|skipped| SkippedLine {
+                line_number: lines_before + skipped.line_number,
+                ..skipped
+            }));
+        lines_before += lines;
+    }
+
+    Ok(calibration)
+}
+
+/// Splits `document` into chunks of at least `chunk_size` bytes that end right after a newline,
+/// except for the last one, which has whatever is left.
+pub fn chunks(document: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = vec![];
+    let mut rest = document;
+    while !rest.is_empty() {
+        let last = chunk_size.min(rest.len()) - 1;
+        let end = match memchr::memchr(b'\n', &rest[last..]) {
+            Some(newline) => last + newline + 1,
+            None => rest.len(),
+        };
+        let (chunk, next) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = next;
+    }
+
+    chunks
+}
+
+/// The calibration of a chunk with line numbers starting at 1, and the number of lines in it. Fails
+/// with the line number if a line's value is too large.
+fn calibrate_chunk(
+    chunk: &[u8],
+    scanner: &Scanner,
+    extraction: &Extraction,
+) -> Result<(Calibration, usize), (usize, eyre::Report)> {
+    let mut calibration = Calibration::default();
+    let mut line_number = 0;
+    for line in lines(chunk) {
+        line_number += 1;
+        let value = extract(line, scanner, extraction).map_err(|err| (line_number, err))?;
+        match value {
+            Some(value) => {
+                calibration.sum = calibration
+                    .sum
+                    .checked_add(value)
+                    .wrap_err("sum of the calibration values too large")
+                    .map_err(|err| (line_number, err))?;
+            }
+            None => calibration.skipped.push(SkippedLine {
+                line_number,
+                content: String::from_utf8_lossy(line).trim_end().to_string(),
+            }),
+        }
+    }
+
+    Ok((calibration, line_number))
+}
+
+/// The lines of `chunk` without their line endings, split like [`str::lines`] splits them.
+fn lines(chunk: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = chunk;
+    iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (line, next) = match memchr::memchr(b'\n', rest) {
+            Some(newline) => (&rest[..newline], &rest[newline + 1..]),
+            None => (rest, &[][..]),
+        };
+        rest = next;
+
+        Some(line.trim_ascii_end())
+    })
+}
+
+/// A calibration document of exactly `size` bytes mixing letters, digits and the English words
+/// for digits, the same for every run. Generated on all threads, as the interesting documents are
+/// several gigabytes large.
+pub fn generate_document(size: usize) -> Vec<u8> {
+    let mut document = vec![0; size];
+    document
+        .par_chunks_mut(BLOCK_SIZE)
+        .enumerate()
+        .for_each(|(idx, block)| generate_block(block, idx as u64));
+
+    document
+}
+
+/// Fills `block` with lines of 10 to 50 bytes, the last one cut short to fit.
+fn generate_block(block: &mut [u8], seed: u64) {
+    // xorshift keeps the documents reproducible without pulling in a random number generator,
+    // every block gets its own state so they can be generated independently
+    let mut state = 0x2545_f491_4f6c_dd1d ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let mut next = |bound: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound) as usize
+    };
+
+    let mut pos = 0;
+    while pos < block.len() {
+        let end = pos + (10 + next(40)).min(block.len() - pos - 1);
+        while pos < end {
+            let word = WORDS[next(9)].as_bytes();
+            match next(20) {
+                0 if pos + word.len() <= end => {
+                    block[pos..pos + word.len()].copy_from_slice(word);
+                    pos += word.len();
+                    continue;
+                }
+                1 => block[pos] = b'0' + next(10) as u8,
+                _ => block[pos] = b'a' + next(26) as u8,
+            }
+            pos += 1;
+        }
+        block[pos] = b'\n';
+        pos += 1;
+    }
+}
+
+/// Parses sizes like `512M` or `4G`, in powers of 1000 like MB/s.
+pub fn parse_size(s: &str) -> eyre::Result<usize> {
+    let (number, unit) = s
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or((s, ""), |idx| s.split_at(idx));
+    let factor: usize = match unit {
+        "" => 1,
+        "K" => 1_000,
+        "M" => 1_000_000,
+        "G" => 1_000_000_000,
+        _ => bail!("unknown unit {unit:?}, expected K, M or G"),
+    };
+    let number = number
+        .parse::<usize>()
+        .wrap_err_with(|| format!("invalid size: {s}"))?;
+
+    number.checked_mul(factor).wrap_err("size too large")
+}
+
+/// Generates a document of the size given via the `THROUGHPUT` env var, e.g. `4G`, calibrates it
+/// with [`calibrate_parallel`] and prints how many MB/s that took. The number of threads can be
+/// changed with rayon's `RAYON_NUM_THREADS`. Returns whether it was set, in which case there's
+/// nothing left to do.
+pub fn throughput_from_env(scanner: &Scanner, extraction: &Extraction) -> eyre::Result<bool> {
+    let Ok(size) = env::var("THROUGHPUT") else {
+        return Ok(false);
+    };
+    let size = parse_size(&size)?;
+
+    let start = Instant::now();
+    let document = generate_document(size);
+    println!(
+        "Generated {:.2} GB in {:.2?}",
+        size as f64 / 1e9,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let calibration = calibrate_parallel(&document, scanner, extraction)?;
+    let elapsed = start.elapsed();
+    println!(
+        "Calibrated {:.2} GB on {} threads in {elapsed:.2?}: {:.1} MB/s, the sum is {}",
+        size as f64 / 1e9,
+        rayon::current_num_threads(),
+        size as f64 / 1e6 / elapsed.as_secs_f64(),
+        calibration.sum
+    );
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::calibrate;
+    use aoc_2023_common::PuzzlePart;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_chunks() {
+        let document = b"ab\ncd\n\nef";
+        assert_eq!(
+            chunks(document, 1),
+            [&b"ab\n"[..], &b"cd\n"[..], &b"\n"[..], &b"ef"[..]]
+        );
+        assert_eq!(chunks(document, 4), [&b"ab\ncd\n"[..], &b"\nef"[..]]);
+        assert_eq!(chunks(document, 100), [&document[..]]);
+        assert!(chunks(b"", 4).is_empty());
+    }
+
+    #[test]
+    fn test_calibrate_parallel() -> eyre::Result<()> {
+        let input = include_str!("input.txt");
+        let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+        let extraction = Extraction::default();
+
+        let calibration = calibrate_parallel(input.as_bytes(), scanner, &extraction)?;
+        assert_eq!(calibration.sum, 55902);
+
+        let document = "1abc2\r\nnothing\n\ntwo\n\u{feff}x";
+        let expected = calibrate(document.as_bytes(), scanner, &extraction)?;
+        for chunk_size in [1, 3, 8, 100] {
+            let calibration =
+                calibrate_chunked(document.as_bytes(), scanner, &extraction, chunk_size)?;
+            assert_eq!(calibration, expected);
+        }
+        assert_eq!(expected.skipped.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibrate_parallel_overflow() {
+        let all = Extraction::new("all".parse().unwrap());
+        let document = format!("1\n2\n{}\n", "9".repeat(30));
+        let err = calibrate_chunked(
+            document.as_bytes(),
+            Scanner::for_puzzle_part(PuzzlePart::One),
+            &all,
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "in line 3");
+    }
+
+    #[test]
+    fn test_generate_document() -> eyre::Result<()> {
+        let document = generate_document(BLOCK_SIZE * 2 + 5);
+        assert_eq!(document.len(), BLOCK_SIZE * 2 + 5);
+        assert_eq!(document, generate_document(BLOCK_SIZE * 2 + 5));
+        assert!(document.is_ascii());
+
+        let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+        assert_eq!(
+            calibrate_parallel(&document, scanner, &Extraction::default())?,
+            calibrate(&document[..], scanner, &Extraction::default())?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_size() -> eyre::Result<()> {
+        assert_eq!(parse_size("512")?, 512);
+        assert_eq!(parse_size("64M")?, 64_000_000);
+        assert_eq!(parse_size("4G")?, 4_000_000_000);
+        assert!(parse_size("4T").is_err());
+        assert!(parse_size("G").is_err());
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_calibrate_chunked_matches_calibrate(
+            document in "([a-z0-9]{0,8}(one|two|eight)?[a-z0-9]{0,8}\r?\n){0,20}[a-z0-9]{0,8}",
+            chunk_size in 1usize..64,
+        ) {
+            let scanner = Scanner::for_puzzle_part(PuzzlePart::Two);
+            let extraction = Extraction::default();
+            prop_assert_eq!(
+                calibrate_chunked(document.as_bytes(), scanner, &extraction, chunk_size).ok(),
+                calibrate(document.as_bytes(), scanner, &extraction).ok()
+            );
+        }
+    }
+}
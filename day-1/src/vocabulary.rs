@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::str::FromStr;
+
+use aoc_2023_common::PuzzlePart;
+use eyre::{bail, eyre, WrapErr};
+
+use crate::checked_concat;
+
+const ENGLISH: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+const GERMAN: [(&str, u32); 10] = [
+    ("null", 0),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+const FRENCH: [(&str, u32); 10] = [
+    ("zéro", 0),
+    ("un", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+const ORDINALS: [(&str, u32); 9] = [
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("sixth", 6),
+    ("seventh", 7),
+    ("eighth", 8),
+    ("ninth", 9),
+];
+const ZERO_AND_TEN: [(&str, u32); 2] = [("zero", 0), ("ten", 10)];
+/// The words for the digits above 9 in base 16.
+const HEX: [(&str, u32); 6] = [
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+];
+
+/// The built-in vocabularies by name.
+pub const BUILTIN: [(&str, &[(&str, u32)]); 6] = [
+    ("english", &ENGLISH),
+    ("german", &GERMAN),
+    ("french", &FRENCH),
+    ("ordinals", &ORDINALS),
+    ("zero-ten", &ZERO_AND_TEN),
+    ("hex", &HEX),
+];
+
+/// The spelled out numbers recognized in calibration documents besides the digits themselves.
+///
+/// Vocabularies are written one word per line as `word = number`, with empty lines and lines
+/// starting with `#` ignored. Numbers above 9 are written out in full in calibration values, so
+/// `ten` and `3` make `103`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: BTreeMap<String, u32>,
+}
+
+impl Vocabulary {
+    /// The empty vocabulary, which only recognizes digits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The vocabulary of puzzle part two.
+    pub fn english() -> Self {
+        Self::from_words(ENGLISH).expect("the English words are unique")
+    }
+
+    pub fn for_puzzle_part(puzzle_part: PuzzlePart) -> Self {
+        match puzzle_part {
+            PuzzlePart::One => Self::new(),
+            PuzzlePart::Two => Self::english(),
+        }
+    }
+
+    pub fn builtin(name: &str) -> eyre::Result<Self> {
+        let Some((_, words)) = BUILTIN.iter().find(|(builtin, _)| *builtin == name) else {
+            let names = BUILTIN.map(|(name, _)| name);
+            bail!(
+                "unknown vocabulary {name}, expected one of {}",
+                names.join(", ")
+            );
+        };
+
+        Self::from_words(words.iter().copied())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("couldn't read vocabulary {}", path.display()))?
+            .parse()
+            .wrap_err_with(|| format!("invalid vocabulary {}", path.display()))
+    }
+
+    /// The vocabulary given via the `VOCABULARY` env var: built-in names or paths to vocabulary
+    /// files, separated by commas, e.g. `english,zero-ten`. Returns `None` if it isn't set.
+    pub fn from_env() -> eyre::Result<Option<Self>> {
+        let Ok(spec) = env::var("VOCABULARY") else {
+            return Ok(None);
+        };
+
+        let vocabulary = spec.split(',').try_fold(Self::new(), |vocabulary, name| {
+            let other = if BUILTIN.iter().any(|(builtin, _)| *builtin == name) {
+                Self::builtin(name)?
+            } else {
+                Self::load(name)?
+            };
+            vocabulary.merge(other)
+        })?;
+
+        Ok(Some(vocabulary))
+    }
+
+    fn from_words<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> eyre::Result<Self> {
+        words
+            .into_iter()
+            .try_fold(Self::new(), |vocabulary, (word, number)| {
+                vocabulary.with(word, number)
+            })
+    }
+
+    /// Adds a word. Adding a word again is fine as long as it stands for the same number. Numbers
+    /// have to fit into a calibration value twice, as the first and the last digit.
+    pub fn with(mut self, word: &str, number: u32) -> eyre::Result<Self> {
+        if word.is_empty() {
+            bail!("words can't be empty");
+        }
+        if checked_concat(number, number).is_none() {
+            bail!("{number} is too large for a calibration value");
+        }
+        match self.words.insert(word.to_string(), number) {
+            Some(previous) if previous != number => {
+                bail!("{word} stands for both {previous} and {number}")
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// All words of both vocabularies.
+    pub fn merge(self, other: Self) -> eyre::Result<Self> {
+        other
+            .words
+            .into_iter()
+            .try_fold(self, |vocabulary, (word, number)| {
+                vocabulary.with(&word, number)
+            })
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words
+            .iter()
+            .map(|(word, &number)| (word.as_str(), number))
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .try_fold(Self::new(), |vocabulary, (line_number, line)| {
+                let (word, number) = line
+                    .split_once('=')
+                    .ok_or_else(|| eyre!("expected `word = number`, got {line:?}"))
+                    .and_then(|(word, number)| {
+                        let number = number.trim().parse().wrap_err("invalid number")?;
+                        Ok((word.trim(), number))
+                    })
+                    .wrap_err_with(|| format!("in line {line_number}"))?;
+
+                vocabulary
+                    .with(word, number)
+                    .wrap_err_with(|| format!("in line {line_number}"))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{calibration_value_with_scanner, Scanner};
+
+    #[test]
+    fn test_vocabulary_parse() -> eyre::Result<()> {
+        let vocabulary = Vocabulary::from_str(
+            "\
+# Dutch
+een = 1
+twee = 2
+
+tien = 10
+",
+        )?;
+        assert_eq!(
+            vocabulary.words().collect::<Vec<_>>(),
+            [("een", 1), ("tien", 10), ("twee", 2)]
+        );
+
+        let err = Vocabulary::from_str("een = 1\ntwee 2").unwrap_err();
+        assert_eq!(err.to_string(), "in line 2");
+        assert_eq!(
+            err.root_cause().to_string(),
+            r#"expected `word = number`, got "twee 2""#
+        );
+
+        let err = Vocabulary::from_str("een = 1\neen = 2").unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "een stands for both 1 and 2");
+
+        let err = Vocabulary::from_str("een = 1\nveel = 4000000000").unwrap_err();
+        assert_eq!(err.to_string(), "in line 2");
+        assert_eq!(
+            err.root_cause().to_string(),
+            "4000000000 is too large for a calibration value"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vocabulary_number_limit() -> eyre::Result<()> {
+        // 42949 written twice is just below u32::MAX
+        let vocabulary = Vocabulary::new().with("big", 42949)?;
+        let scanner = Scanner::new(&vocabulary);
+        assert_eq!(
+            calibration_value_with_scanner("xbigx", &scanner),
+            Some(4294942949)
+        );
+        assert!(Vocabulary::new().with("bigger", 42950).is_err());
+        assert!(Vocabulary::new().with("max", u32::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vocabulary_builtin() -> eyre::Result<()> {
+        for (name, words) in BUILTIN {
+            assert_eq!(Vocabulary::builtin(name)?.words().count(), words.len());
+        }
+        assert_eq!(Vocabulary::builtin("english")?, Vocabulary::english());
+
+        let merged = Vocabulary::english().merge(Vocabulary::builtin("zero-ten")?)?;
+        assert_eq!(merged.words().count(), 11);
+
+        let err = Vocabulary::builtin("klingon").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown vocabulary klingon, expected one of english, german, french, ordinals, zero-ten, hex"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vocabulary_load() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("dutch.txt");
+        std::fs::write(&path, "een = 1\ntwee = 2\n")?;
+
+        assert_eq!(Vocabulary::load(&path)?.words().count(), 2);
+        assert!(Vocabulary::load(dir.path().join("missing.txt")).is_err());
+
+        Ok(())
+    }
+}
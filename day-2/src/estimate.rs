@@ -0,0 +1,200 @@
+//! Estimates which bag games were played with, assuming every draw takes its cubes from the bag
+//! without replacement and puts them back before the next one.
+
+use std::collections::BTreeMap;
+
+use crate::{bags_of_total, CubeSet, Game};
+
+/// The natural logarithm of the number of ways to choose `k` of `n` things.
+fn ln_choose(n: u32, k: u32) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+
+    (0..k.min(n - k))
+        .map(|i| f64::from(n - i).ln() - f64::from(i + 1).ln())
+        .sum()
+}
+
+/// The natural logarithm of [`draw_probability`].
+pub fn ln_draw_probability(draw: &CubeSet, bag: &CubeSet) -> f64 {
+    // a draw larger than the bag would otherwise be `-inf - -inf`, which is NaN
+    if !draw.fits_in(bag) {
+        return f64::NEG_INFINITY;
+    }
+
+    let size = draw.iter().map(|(_, amount)| amount).sum();
+    let total = bag.iter().map(|(_, amount)| amount).sum();
+    let ways = draw
+        .iter()
+        .map(|(color, amount)| ln_choose(bag.get(color), amount))
+        .sum::<f64>();
+
+    ways - ln_choose(total, size)
+}
+
+/// The probability of drawing exactly the cubes of `draw` when drawing as many cubes from `bag`.
+pub fn draw_probability(draw: &CubeSet, bag: &CubeSet) -> f64 {
+    ln_draw_probability(draw, bag).exp()
+}
+
+/// The natural logarithm of [`game_probability`].
+pub fn ln_game_probability(game: &Game, bag: &CubeSet) -> f64 {
+    game.cube_sets
+        .iter()
+        .map(|draw| ln_draw_probability(draw, bag))
+        .sum()
+}
+
+/// The probability of drawing exactly the draws of `game` from `bag`, which is 0 if the game isn't
+/// possible with it.
+pub fn game_probability(game: &Game, bag: &CubeSet) -> f64 {
+    ln_game_probability(game, bag).exp()
+}
+
+/// The natural logarithm of the probability of all draws of `games` with `bag`.
+pub fn ln_likelihood(games: &[Game], bag: &CubeSet) -> f64 {
+    games
+        .iter()
+        .map(|game| ln_game_probability(game, bag))
+        .sum()
+}
+
+/// The bag with `total` cubes that makes the draws of `games` the most likely, see
+/// [`bags_of_total`] for which bags there are. `None` if no such bag makes all games possible.
+pub fn most_likely_bag(games: &[Game], total: u32) -> Option<CubeSet> {
+    bags_of_total(games, total)
+        .into_iter()
+        .map(|bag| (ln_likelihood(games, &bag), bag))
+        .max_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs))
+        .map(|(_, bag)| bag)
+}
+
+/// How likely each bag with `total` cubes is after seeing the draws of `games`, if every one of
+/// them was as likely before. Ordered from the most to the least likely bag.
+pub fn posterior(games: &[Game], total: u32) -> Vec<(CubeSet, f64)> {
+    let mut bags = bags_of_total(games, total)
+        .into_iter()
+        .map(|bag| (ln_likelihood(games, &bag), bag))
+        .collect::<Vec<_>>();
+    bags.sort_by(|(lhs, _), (rhs, _)| rhs.total_cmp(lhs));
+
+    // relative to the most likely bag, so the likelihoods don't all underflow to 0
+    let Some(&(max, _)) = bags.first() else {
+        return vec![];
+    };
+    let evidence = bags
+        .iter()
+        .map(|(ln_likelihood, _)| (ln_likelihood - max).exp())
+        .sum::<f64>();
+
+    bags.into_iter()
+        .map(|(ln_likelihood, bag)| (bag, (ln_likelihood - max).exp() / evidence))
+        .collect()
+}
+
+/// A game with id `id` played with `bag`, with a draw for every size in `draw_sizes`. Games are
+/// the same for the same `seed`.
+pub fn simulate_game(id: u32, bag: &CubeSet, draw_sizes: &[u32], seed: u64) -> Game {
+    // xorshift keeps the games reproducible without pulling in a random number generator
+    let mut state = 0x2545_f491_4f6c_dd1d ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+
+    let mut cubes = bag
+        .iter()
+        .flat_map(|(color, amount)| (0..amount).map(move |_| color))
+        .collect::<Vec<_>>();
+    let cube_sets = draw_sizes
+        .iter()
+        .map(|&size| {
+            // the first `size` cubes of a partial Fisher-Yates shuffle are a draw without
+            // replacement, the cubes are all back in the bag for the next one
+            let size = (size as usize).min(cubes.len());
+            for idx in 0..size {
+                let other = idx + next(cubes.len() - idx);
+                cubes.swap(idx, other);
+            }
+
+            let mut drawn = BTreeMap::<&str, u32>::new();
+            for color in &cubes[..size] {
+                *drawn.entry(color).or_default() += 1;
+            }
+            CubeSet::from_iter(drawn)
+        })
+        .collect();
+
+    Game { id, cube_sets }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_draw_probability() -> eyre::Result<()> {
+        let bag = CubeSet::from_str("2 red, 1 blue")?;
+        assert!((draw_probability(&CubeSet::from_str("1 red")?, &bag) - 2.0 / 3.0).abs() < 1e-9);
+        assert!(
+            (draw_probability(&CubeSet::from_str("1 red, 1 blue")?, &bag) - 2.0 / 3.0).abs() < 1e-9
+        );
+        assert!((draw_probability(&CubeSet::from_str("2 red")?, &bag) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(draw_probability(&CubeSet::from_str("2 blue")?, &bag), 0.0);
+        assert_eq!(draw_probability(&CubeSet::from_str("1 green")?, &bag), 0.0);
+        assert_eq!(draw_probability(&CubeSet::from_str("4 red")?, &bag), 0.0);
+        assert_eq!(
+            draw_probability(&CubeSet::from_str("3 red, 1 blue")?, &bag),
+            0.0
+        );
+
+        let game = Game::from_str("Game 1: 1 red; 2 red")?;
+        assert!((game_probability(&game, &bag) - 2.0 / 9.0).abs() < 1e-9);
+        let game = Game::from_str("Game 2: 1 red; 2 blue")?;
+        assert_eq!(game_probability(&game, &bag), 0.0);
+        let game = Game::from_str("Game 3: 1 red; 5 red, 1 blue")?;
+        assert_eq!(game_probability(&game, &bag), 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate_game() -> eyre::Result<()> {
+        let bag = CubeSet::from_str("3 red, 2 green, 1 blue")?;
+        let game = simulate_game(1, &bag, &[3, 6, 0, 10], 7);
+        assert_eq!(game, simulate_game(1, &bag, &[3, 6, 0, 10], 7));
+        assert_eq!(game.cube_sets[1], bag);
+        assert_eq!(game.cube_sets[2], CubeSet::empty());
+        assert_eq!(game.cube_sets[3], bag);
+        assert!(game.is_possible(&bag));
+        assert!(game_probability(&game, &bag) > 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_simulated_bag() -> eyre::Result<()> {
+        let bag = CubeSet::from_str("6 red, 3 green, 1 blue")?;
+        let games = (1..=100)
+            .map(|id| simulate_game(id, &bag, &[4, 2, 5], u64::from(id)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(most_likely_bag(&games, 10), Some(bag.clone()));
+        assert_eq!(most_likely_bag(&games, 2), None);
+
+        let posterior = posterior(&games, 10);
+        assert_eq!(posterior[0].0, bag);
+        assert!(posterior[0].1 > 0.9);
+        let total = posterior
+            .iter()
+            .map(|(_, probability)| probability)
+            .sum::<f64>();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1060 @@
+use aoc_2023_common::input::try_fold_lines;
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::PuzzlePart;
+use eyre::{bail, ContextCompat, WrapErr};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+use tracing::{instrument, warn};
+
+pub mod estimate;
+
+/// The colors of the puzzle, which come first when cube sets are rendered.
+pub const PUZZLE_COLORS: [&str; 3] = ["red", "green", "blue"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub cube_sets: Vec<CubeSet>,
+}
+
+/// Some cubes of any number of colors. Colors without cubes aren't stored, so sets with the same
+/// cubes are equal no matter which colors were mentioned with 0 cubes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CubeSet {
+    cubes: BTreeMap<String, u32>,
+}
+
+/// The colors cube sets may have.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// Any color, as found in the input.
+    #[default]
+    Discovered,
+    /// Only these colors, `unknown` decides what happens with the others.
+    Declared {
+        colors: BTreeSet<String>,
+        unknown: UnknownColor,
+    },
+}
+
+/// What happens with colors outside a declared [`Palette`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UnknownColor {
+    /// Leave their cubes out.
+    Ignore,
+    /// Leave their cubes out, but log a warning.
+    #[default]
+    Warn,
+    /// Fail parsing.
+    Reject,
+}
+
+impl FromStr for UnknownColor {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = match s {
+            "ignore" => UnknownColor::Ignore,
+            "warn" => UnknownColor::Warn,
+            "reject" => UnknownColor::Reject,
+            _ => bail!("unknown handling of unknown colors: {s}"),
+        };
+
+        Ok(unknown)
+    }
+}
+
+impl Palette {
+    /// Only `colors`, warning about others.
+    pub fn declared<'a>(colors: impl IntoIterator<Item = &'a str>) -> Self {
+        Self::Declared {
+            colors: colors.into_iter().map(str::to_string).collect(),
+            unknown: UnknownColor::default(),
+        }
+    }
+
+    /// The palette given via the `PALETTE` env var as colors separated by commas, with unknown
+    /// colors handled as given via `UNKNOWN_COLORS`: `ignore`, `warn` or `reject`. Defaults to
+    /// [`Palette::Discovered`].
+    pub fn from_env() -> eyre::Result<Self> {
+        let Ok(colors) = env::var("PALETTE") else {
+            return Ok(Self::Discovered);
+        };
+        let unknown = match env::var("UNKNOWN_COLORS") {
+            Ok(unknown) => unknown.parse()?,
+            Err(_) => UnknownColor::default(),
+        };
+
+        Ok(Self::Declared {
+            colors: colors
+                .split(',')
+                .map(|color| color.trim().to_string())
+                .collect(),
+            unknown,
+        })
+    }
+
+    /// Whether `color` is part of the palette.
+    pub fn contains(&self, color: &str) -> bool {
+        match self {
+            Palette::Discovered => true,
+            Palette::Declared { colors, .. } => colors.contains(color),
+        }
+    }
+
+    /// Whether cubes of `color` are part of a cube set. Fails if the color is rejected.
+    pub fn accepts(&self, color: &str) -> eyre::Result<bool> {
+        let Palette::Declared { colors, unknown } = self else {
+            return Ok(true);
+        };
+        if colors.contains(color) {
+            return Ok(true);
+        }
+
+        match unknown {
+            UnknownColor::Ignore => Ok(false),
+            UnknownColor::Warn => {
+                warn!(color, "ignoring a color outside the palette");
+                Ok(false)
+            }
+            UnknownColor::Reject => bail!("{color} isn't in the palette"),
+        }
+    }
+
+    /// The power of `cube_set`. Unlike [`CubeSet::power`], colors of a declared palette are part
+    /// of the product even without cubes.
+    pub fn power(&self, cube_set: &CubeSet) -> u32 {
+        match self {
+            Palette::Discovered => cube_set.power(),
+            Palette::Declared { colors, .. } => {
+                colors.iter().map(|color| cube_set.get(color)).product()
+            }
+        }
+    }
+}
+
+/// How forgiving parsing game records is.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Malformed cubes are skipped, colors drawn twice in one draw are summed up and game ids can
+    /// be anything, like the puzzle expects.
+    #[default]
+    Lenient,
+    /// Malformed cubes, colors drawn twice in one draw, colors outside the palette, empty draws
+    /// and game ids that don't count up from 1 are errors, with the column they're at.
+    Strict,
+}
+
+impl FromStr for Mode {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            _ => bail!("unknown mode: {s}, expected strict or lenient"),
+        }
+    }
+}
+
+impl Mode {
+    /// The mode given via the `VALIDATION` env var. Defaults to [`Mode::Lenient`].
+    pub fn from_env() -> eyre::Result<Mode> {
+        match env::var("VALIDATION") {
+            Ok(val) => val.parse(),
+            Err(_) => Ok(Mode::default()),
+        }
+    }
+}
+
+impl FromStr for Game {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &Palette::Discovered)
+    }
+}
+
+impl Game {
+    /// Parses a game whose cube sets only have colors of `palette`.
+    pub fn parse(s: &str, palette: &Palette) -> eyre::Result<Self> {
+        static GAME_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"Game (?<game_id>\d+):(?<cube_sets>.*)").unwrap());
+        let game_captures = GAME_REGEX
+            .captures(s)
+            .wrap_err("game pattern didn't match")?;
+
+        let game_id_match = game_captures
+            .name("game_id")
+            .context("invalid capture group name")?;
+        let cube_sets_match = game_captures
+            .name("cube_sets")
+            .context("invalid capture group name")?;
+
+        let game_id: u32 = game_id_match.as_str().parse()?;
+        let cube_sets = cube_sets_match
+            .as_str()
+            .split(';')
+            .map(|cube_set| CubeSet::parse(cube_set.trim(), palette))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: game_id,
+            cube_sets,
+        })
+    }
+
+    /// Same as [`Game::parse`], but in [`Mode::Strict`]. Errors start with the 1-based column
+    /// they're at.
+    pub fn parse_strict(s: &str, palette: &Palette) -> eyre::Result<Self> {
+        const PREFIX: &str = "Game ";
+        let (id, cube_sets) = s
+            .strip_prefix(PREFIX)
+            .and_then(|rest| rest.split_once(": "))
+            .wrap_err("column 1: expected `Game <id>: `")?;
+        let mut offset = PREFIX.len() + id.len() + 2;
+        let id = id
+            .parse()
+            .wrap_err_with(|| format!("column {}: invalid game id: {id}", PREFIX.len() + 1))?;
+
+        let cube_sets = cube_sets
+            .split("; ")
+            .map(|cube_set| {
+                let parsed = CubeSet::parse_strict(cube_set, palette, offset + 1);
+                offset += cube_set.len() + 2;
+                parsed
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self { id, cube_sets })
+    }
+
+    /// Parses a game like [`Game::parse`] or [`Game::parse_strict`], depending on `mode`.
+    pub fn parse_with_mode(s: &str, palette: &Palette, mode: Mode) -> eyre::Result<Self> {
+        match mode {
+            Mode::Lenient => Self::parse(s, palette),
+            Mode::Strict => Self::parse_strict(s, palette),
+        }
+    }
+}
+
+impl FromStr for CubeSet {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &Palette::Discovered)
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, u32)> for CubeSet {
+    /// The cubes of each color, the last amount wins if a color is given more than once.
+    fn from_iter<T: IntoIterator<Item = (S, u32)>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(CubeSet::empty(), |acc, (color, amount)| {
+                acc.with(color, amount)
+            })
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (idx, cube_set) in self.cube_sets.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{cube_set}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CubeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (color, amount)) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{amount} {color}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CubeSet {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// The bag of the puzzle, with 12 red, 13 green and 14 blue cubes.
+    pub fn puzzle_bag() -> Self {
+        Self::from_iter(PUZZLE_COLORS.into_iter().zip([12, 13, 14]))
+    }
+
+    /// Parses a cube set like `3 blue, 4 red`, only keeping the colors of `palette`. An empty
+    /// string is an empty draw.
+    pub fn parse(s: &str, palette: &Palette) -> eyre::Result<Self> {
+        s.split(',')
+            .filter_map(|color_and_amount| {
+                let mut split = color_and_amount.trim().split(' ');
+                Some((
+                    split.next()?.parse::<u32>().ok()?,
+                    split.next()?.to_string(),
+                ))
+            })
+            .try_fold(CubeSet::empty(), |acc, (amount, color)| {
+                if !palette.accepts(&color)? {
+                    return Ok(acc);
+                }
+                let amount = acc
+                    .get(&color)
+                    .checked_add(amount)
+                    .wrap_err_with(|| format!("too many {color} cubes"))?;
+
+                Ok(acc.with(color, amount))
+            })
+    }
+
+    /// Same as [`CubeSet::parse`], but in [`Mode::Strict`]. `column` is where the cube set starts in
+    /// its line, errors start with the column they're at.
+    pub fn parse_strict(s: &str, palette: &Palette, column: usize) -> eyre::Result<Self> {
+        let mut cube_set = CubeSet::empty();
+        let mut seen = BTreeSet::new();
+        let mut offset = 0;
+        for color_and_amount in s.split(", ") {
+            let column = column + offset;
+            offset += color_and_amount.len() + 2;
+
+            let (amount, color) = color_and_amount
+                .split_once(' ')
+                .filter(|(_, color)| !color.is_empty() && !color.contains(' '))
+                .wrap_err_with(|| format!("column {column}: expected `<amount> <color>`"))?;
+            let color_column = column + amount.len() + 1;
+            let amount = amount
+                .parse::<u32>()
+                .wrap_err_with(|| format!("column {column}: invalid number of cubes: {amount}"))?;
+            if !palette.contains(color) {
+                bail!("column {color_column}: {color} isn't in the palette");
+            }
+            // colors with 0 cubes aren't stored in the set, so they're remembered separately
+            if !seen.insert(color) {
+                bail!("column {color_column}: {color} is drawn twice in one draw");
+            }
+            cube_set = cube_set.with(color, amount);
+        }
+
+        if cube_set.cubes.is_empty() {
+            bail!("column {column}: empty draw");
+        }
+
+        Ok(cube_set)
+    }
+
+    /// The same cubes, but with `amount` cubes of `color`.
+    pub fn with(mut self, color: impl Into<String>, amount: u32) -> Self {
+        let color = color.into();
+        if amount == 0 {
+            self.cubes.remove(&color);
+        } else {
+            self.cubes.insert(color, amount);
+        }
+        self
+    }
+
+    /// The number of cubes of `color`.
+    pub fn get(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    /// The colors with cubes and their amounts, the puzzle's colors first and the others in
+    /// alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        let mut cubes = self
+            .cubes
+            .iter()
+            .map(|(color, amount)| (color.as_str(), *amount))
+            .collect::<Vec<_>>();
+        cubes.sort_by_key(|(color, _)| {
+            let rank = PUZZLE_COLORS
+                .iter()
+                .position(|puzzle_color| puzzle_color == color);
+            (rank.unwrap_or(PUZZLE_COLORS.len()), *color)
+        });
+
+        cubes.into_iter()
+    }
+
+    pub fn componentwise_max(mut self, rhs: CubeSet) -> CubeSet {
+        for (color, amount) in rhs.cubes {
+            let component = self.cubes.entry(color).or_default();
+            *component = (*component).max(amount);
+        }
+        self
+    }
+
+    /// The product of the cubes of the puzzle's colors and every other color in the set. Like in
+    /// the puzzle, a set without cubes of one of the puzzle's colors has no power.
+    pub fn power(&self) -> u32 {
+        let other_colors = self
+            .cubes
+            .iter()
+            .filter(|(color, _)| !PUZZLE_COLORS.contains(&color.as_str()))
+            .map(|(_, amount)| amount);
+
+        PUZZLE_COLORS
+            .iter()
+            .map(|color| self.get(color))
+            .chain(other_colors.copied())
+            .product()
+    }
+
+    /// Whether there are at most as many cubes of each color as in `bag`.
+    pub fn fits_in(&self, bag: &CubeSet) -> bool {
+        self.cubes
+            .iter()
+            .all(|(color, amount)| *amount <= bag.get(color))
+    }
+
+    /// The bag given via the `BAG` env var, written like a draw, e.g. `12 red, 13 green, 14 blue`.
+    /// Defaults to [`CubeSet::puzzle_bag`].
+    pub fn bag_from_env() -> eyre::Result<CubeSet> {
+        match env::var("BAG") {
+            Ok(bag) => bag.parse(),
+            Err(_) => Ok(Self::puzzle_bag()),
+        }
+    }
+}
+
+impl Game {
+    /// Whether the game could have been played with the cubes in `bag`.
+    pub fn is_possible(&self, bag: &CubeSet) -> bool {
+        self.cube_sets.iter().all(|cube_set| cube_set.fits_in(bag))
+    }
+
+    /// The fewest cubes of each color the bag could have contained.
+    pub fn minimum_set(&self) -> CubeSet {
+        self.cube_sets
+            .iter()
+            .cloned()
+            .fold(CubeSet::empty(), CubeSet::componentwise_max)
+    }
+}
+
+/// The fewest cubes of each color a bag needs for all `games` to be possible.
+pub fn smallest_bag<'a>(games: impl IntoIterator<Item = &'a Game>) -> CubeSet {
+    games
+        .into_iter()
+        .map(Game::minimum_set)
+        .fold(CubeSet::empty(), CubeSet::componentwise_max)
+}
+
+/// Same as [`smallest_bag`], but only for the games with the given ids. Fails if one of them isn't
+/// in `games`.
+pub fn smallest_bag_for_ids(games: &[Game], ids: &[u32]) -> eyre::Result<CubeSet> {
+    let games = ids
+        .iter()
+        .map(|id| {
+            games
+                .iter()
+                .find(|game| game.id == *id)
+                .wrap_err_with(|| format!("there's no game {id}"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    Ok(smallest_bag(games))
+}
+
+/// All bags with exactly `total` cubes of the colors drawn in `games` that every game is possible
+/// with, ordered by their cubes of each color like [`CubeSet::iter`] orders the colors.
+pub fn bags_of_total(games: &[Game], total: u32) -> Vec<CubeSet> {
+    let smallest = smallest_bag(games);
+    let Some(spare) = total.checked_sub(smallest.cubes.values().sum()) else {
+        return vec![];
+    };
+
+    let colors = smallest
+        .iter()
+        .map(|(color, _)| color.to_string())
+        .collect::<Vec<_>>();
+    let mut bags = vec![];
+    spread(smallest, &colors, spare, &mut bags);
+
+    bags
+}
+
+/// Adds every split of the `spare` cubes among `colors` to `bag`, each split is a different bag.
+fn spread(bag: CubeSet, colors: &[String], spare: u32, bags: &mut Vec<CubeSet>) {
+    match colors {
+        [] if spare == 0 => bags.push(bag),
+        [] => {}
+        [color] => {
+            let amount = bag.get(color) + spare;
+            bags.push(bag.with(color.as_str(), amount));
+        }
+        [color, rest @ ..] => {
+            for extra in 0..=spare {
+                let amount = bag.get(color) + extra;
+                spread(
+                    bag.clone().with(color.as_str(), amount),
+                    rest,
+                    spare - extra,
+                    bags,
+                );
+            }
+        }
+    }
+}
+
+/// A question about which bags the games could have been played with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BagQuery {
+    /// The bags with this many cubes all games are possible with.
+    Total(u32),
+    /// The smallest bag the games with these ids are possible with.
+    Smallest(Vec<u32>),
+    /// The bag with this many cubes that makes the draws of all games the most likely, see
+    /// [`estimate::most_likely_bag`].
+    Likely(u32),
+}
+
+/// Parses `total-<n>`, `smallest-<ids>`, with the ids separated by commas, and `likely-<n>`.
+impl FromStr for BagQuery {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let query = match s.split_once('-') {
+            Some(("total", n)) => BagQuery::Total(
+                n.parse()
+                    .wrap_err_with(|| format!("invalid number of cubes: {n}"))?,
+            ),
+            Some(("smallest", ids)) => BagQuery::Smallest(
+                ids.split(',')
+                    .map(|id| {
+                        id.trim()
+                            .parse()
+                            .wrap_err_with(|| format!("invalid game id: {id}"))
+                    })
+                    .collect::<eyre::Result<_>>()?,
+            ),
+            Some(("likely", n)) => BagQuery::Likely(
+                n.parse()
+                    .wrap_err_with(|| format!("invalid number of cubes: {n}"))?,
+            ),
+            _ => bail!("unknown bag query: {s}"),
+        };
+
+        Ok(query)
+    }
+}
+
+impl BagQuery {
+    /// The query given via the `BAG_QUERY` env var. Returns `None` if it isn't set, in which case
+    /// the puzzle is solved instead.
+    pub fn from_env() -> eyre::Result<Option<BagQuery>> {
+        match env::var("BAG_QUERY") {
+            Ok(val) => Ok(Some(val.parse()?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The bags answering the query for `games`.
+    pub fn answer(&self, games: &[Game]) -> eyre::Result<Vec<CubeSet>> {
+        match self {
+            BagQuery::Total(total) => Ok(bags_of_total(games, *total)),
+            BagQuery::Smallest(ids) => Ok(vec![smallest_bag_for_ids(games, ids)?]),
+            BagQuery::Likely(total) => Ok(estimate::most_likely_bag(games, *total)
+                .into_iter()
+                .collect()),
+        }
+    }
+}
+
+/// The games in `input`, with cubes of the colors of `palette`, parsed in `mode`.
+pub fn parse_games(input: &str, palette: &Palette, mode: Mode) -> eyre::Result<Vec<Game>> {
+    try_fold_lines(normalize(input).as_bytes(), vec![], |mut games, line| {
+        let previous = games.last().map(|game: &Game| game.id);
+        games.push(parse_next_game(line, palette, mode, previous)?);
+        Ok(games)
+    })
+}
+
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<u32> {
+    calculate_result_from_reader(
+        normalize(input).as_bytes(),
+        puzzle_part,
+        &CubeSet::puzzle_bag(),
+        &Palette::Discovered,
+        Mode::Lenient,
+    )
+}
+
+/// Same as [`calculate_result`], but reads the input game by game, parsed in `mode`, and checks
+/// which games are possible with `bag` instead of the puzzle's, only counting the cubes of the
+/// colors of `palette`.
+#[instrument(name = "calculate_result", skip(reader))]
+pub fn calculate_result_from_reader(
+    reader: impl BufRead,
+    puzzle_part: PuzzlePart,
+    bag: &CubeSet,
+    palette: &Palette,
+    mode: Mode,
+) -> eyre::Result<u32> {
+    let (sum, _) = try_fold_lines(reader, (0, None), |(sum, previous), line| {
+        let game = parse_next_game(line, palette, mode, previous)?;
+        let value = match puzzle_part {
+            PuzzlePart::One if game.is_possible(bag) => game.id,
+            PuzzlePart::One => 0,
+            PuzzlePart::Two => palette.power(&game.minimum_set()),
+        };
+
+        let sum = u32::checked_add(sum, value).wrap_err("sum of the results too large")?;
+
+        Ok((sum, Some(game.id)))
+    })?;
+
+    Ok(sum)
+}
+
+/// Parses the game after the one with the `previous` id, which in [`Mode::Strict`] has to have
+/// the id after it, starting at 1.
+fn parse_next_game(
+    line: &str,
+    palette: &Palette,
+    mode: Mode,
+    previous: Option<u32>,
+) -> eyre::Result<Game> {
+    let game = Game::parse_with_mode(line, palette, mode)?;
+    if mode == Mode::Strict {
+        let expected = match previous {
+            Some(id) => id
+                .checked_add(1)
+                .wrap_err_with(|| format!("column 6: there's no game after game {id}"))?,
+            None => 1,
+        };
+        if game.id != expected {
+            bail!("column 6: expected game {expected}, found game {}", game.id);
+        }
+    }
+
+    Ok(game)
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input(input: &str) -> eyre::Result<Vec<Game>> {
+    input.lines().map(Game::from_str).collect()
+}
+
+impl InputPart for Game {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        parse_input(input)
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        parts
+            .iter()
+            .map(Game::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(
+            "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+            PuzzlePart::One,
+        )?;
+        assert_eq!(res, 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(
+            "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+            PuzzlePart::Two,
+        )?;
+        assert_eq!(res, 2286);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_missing_color() -> eyre::Result<()> {
+        let res = calculate_result(
+            "\
+Game 1: 3 blue
+Game 2: 1 red, 2 green; 3 blue
+",
+            PuzzlePart::Two,
+        )?;
+        assert_eq!(res, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_parse() -> eyre::Result<()> {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let game = Game::from_str(input)?;
+
+        assert_eq!(
+            game,
+            Game {
+                id: 1,
+                cube_sets: vec![
+                    CubeSet::from_iter([("red", 4), ("blue", 3)]),
+                    CubeSet::from_iter([("red", 1), ("green", 2), ("blue", 6)]),
+                    CubeSet::from_iter([("green", 2)]),
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_display() -> eyre::Result<()> {
+        let game = Game::from_str("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")?;
+
+        assert_eq!(
+            game.to_string(),
+            "Game 1: 4 red, 3 blue; 1 red, 2 green, 6 blue; 2 green"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_cube_set_display() -> eyre::Result<()> {
+        let game = Game {
+            id: 1,
+            cube_sets: vec![CubeSet::empty()],
+        };
+
+        assert_eq!(game.to_string(), "Game 1: ");
+        assert_eq!(Game::from_str(&game.to_string())?, game);
+        // lines lose their trailing whitespace easily
+        assert_eq!(Game::from_str("Game 1:")?, game);
+        assert_eq!(
+            Game::from_str("Game 2: 3 blue; ")?.cube_sets,
+            [CubeSet::from_iter([("blue", 3)]), CubeSet::empty()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_from_reader() -> eyre::Result<()> {
+        let input = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+";
+        // a tiny buffer makes lines span several reads
+        let reader = BufReader::with_capacity(4, input.as_bytes());
+        assert_eq!(
+            calculate_result_from_reader(
+                reader,
+                PuzzlePart::Two,
+                &CubeSet::puzzle_bag(),
+                &Palette::Discovered,
+                Mode::Lenient,
+            )?,
+            48 + 1560
+        );
+
+        let err = calculate_result_from_reader(
+            "Game 1: 3 blue\nGame two".as_bytes(),
+            PuzzlePart::One,
+            &CubeSet::puzzle_bag(),
+            &Palette::Discovered,
+            Mode::Lenient,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "in line 2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_with_bag() -> eyre::Result<()> {
+        let bag = CubeSet::from_str("20 red, 13 green, 15 blue")?;
+        let res = calculate_result_from_reader(
+            EXAMPLE.as_bytes(),
+            PuzzlePart::One,
+            &bag,
+            &Palette::Discovered,
+            Mode::Lenient,
+        )?;
+        assert_eq!(res, 1 + 2 + 3 + 4 + 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discovered_colors() -> eyre::Result<()> {
+        let game = Game::from_str("Game 7: 2 yellow, 1 red; 3 yellow, 2 cyan, 2 green, 1 blue")?;
+        assert_eq!(
+            game.minimum_set(),
+            CubeSet::from_iter([
+                ("red", 1),
+                ("green", 2),
+                ("blue", 1),
+                ("yellow", 3),
+                ("cyan", 2)
+            ])
+        );
+        assert_eq!(game.minimum_set().power(), 12);
+        assert_eq!(
+            game.minimum_set().to_string(),
+            "1 red, 2 green, 1 blue, 2 cyan, 3 yellow"
+        );
+        assert!(!game.is_possible(&CubeSet::puzzle_bag()));
+        assert!(game.is_possible(&CubeSet::from_str(
+            "1 red, 2 green, 1 blue, 3 yellow, 2 cyan"
+        )?));
+
+        // without any blue cubes, like in the puzzle
+        let game = Game::from_str("Game 8: 2 yellow, 1 red, 4 green")?;
+        assert_eq!(game.minimum_set().power(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_declared_palette() -> eyre::Result<()> {
+        let line = "Game 7: 2 yellow, 1 red; 3 green, 2 cyan";
+        let palette = Palette::declared(PUZZLE_COLORS);
+        let game = Game::parse(line, &palette)?;
+        assert_eq!(
+            game.minimum_set(),
+            CubeSet::from_iter([("red", 1), ("green", 3)])
+        );
+        // blue is part of the palette, but was never drawn
+        assert_eq!(palette.power(&game.minimum_set()), 0);
+
+        let palette = Palette::Declared {
+            colors: ["red", "yellow"].map(str::to_string).into(),
+            unknown: UnknownColor::Reject,
+        };
+        assert_eq!(
+            Game::parse(line, &palette).unwrap_err().to_string(),
+            "green isn't in the palette"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_mode() -> eyre::Result<()> {
+        let palette = Palette::Discovered;
+        assert_eq!(
+            Game::parse_strict("Game 1: 3 blue, 4 red; 2 green", &palette)?,
+            Game::from_str("Game 1: 3 blue, 4 red; 2 green")?
+        );
+
+        let errors = [
+            ("Game 1; 3 blue", "column 1: expected `Game <id>: `"),
+            ("Game x1: 3 blue", "column 6: invalid game id: x1"),
+            (
+                "Game 1: 3 blue; 4 red, x green",
+                "column 24: invalid number of cubes: x",
+            ),
+            (
+                "Game 1: 3 blue; 4 red, 5",
+                "column 24: expected `<amount> <color>`",
+            ),
+            (
+                "Game 1: 3 blue; 4 red, 5 light red",
+                "column 24: expected `<amount> <color>`",
+            ),
+            (
+                "Game 1: 3 blue, 4 red, 10 blue",
+                "column 27: blue is drawn twice in one draw",
+            ),
+            (
+                "Game 1: 0 blue, 0 blue",
+                "column 19: blue is drawn twice in one draw",
+            ),
+            (
+                "Game 1: 3 blue; ; 2 red",
+                "column 17: expected `<amount> <color>`",
+            ),
+            ("Game 1: 3 blue; 0 red", "column 17: empty draw"),
+        ];
+        for (line, err) in errors {
+            assert_eq!(
+                Game::parse_strict(line, &palette).unwrap_err().to_string(),
+                err,
+                "{line}"
+            );
+        }
+
+        let palette = Palette::declared(PUZZLE_COLORS);
+        assert_eq!(
+            Game::parse_strict("Game 1: 3 blue, 12 yellow", &palette)
+                .unwrap_err()
+                .to_string(),
+            "column 20: yellow isn't in the palette"
+        );
+
+        let calculate = |input: &str, mode| {
+            calculate_result_from_reader(
+                input.as_bytes(),
+                PuzzlePart::One,
+                &CubeSet::puzzle_bag(),
+                &Palette::Discovered,
+                mode,
+            )
+        };
+        assert_eq!(calculate(EXAMPLE, Mode::Strict)?, 8);
+        let input = "Game 1: 3 blue\nGame 3: 1 red\n";
+        assert_eq!(calculate(input, Mode::Lenient)?, 4);
+        let err = calculate(input, Mode::Strict).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "in line 2: column 6: expected game 2, found game 3"
+        );
+
+        // the largest id only has a successor in strict mode
+        let input = "Game 4294967295: 30 blue\nGame 1: 1 red\n";
+        assert_eq!(calculate(input, Mode::Lenient)?, 1);
+        assert_eq!(
+            parse_games(input, &Palette::Discovered, Mode::Lenient)?.len(),
+            2
+        );
+        let err = parse_next_game("Game 1: 1 red", &palette, Mode::Strict, Some(u32::MAX));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "column 6: there's no game after game 4294967295"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bag_queries() -> eyre::Result<()> {
+        let games = parse_games(EXAMPLE, &Palette::Discovered, Mode::Strict)?;
+        assert_eq!(
+            smallest_bag_for_ids(&games, &[1, 2])?,
+            CubeSet::from_iter([("red", 4), ("green", 3), ("blue", 6)])
+        );
+        assert_eq!(
+            smallest_bag_for_ids(&games, &[6]).unwrap_err().to_string(),
+            "there's no game 6"
+        );
+
+        // the smallest bag for all games has 20 red, 13 green and 15 blue cubes
+        assert!(bags_of_total(&games, 47).is_empty());
+        assert_eq!(bags_of_total(&games, 48), [smallest_bag(&games)]);
+        let bags = bags_of_total(&games, 50);
+        assert_eq!(bags.len(), 6);
+        assert!(bags
+            .iter()
+            .all(|bag| games.iter().all(|game| game.is_possible(bag))));
+
+        assert_eq!(
+            BagQuery::from_str("smallest-1, 2")?.answer(&games)?,
+            [smallest_bag_for_ids(&games, &[1, 2])?]
+        );
+        assert_eq!(BagQuery::from_str("total-50")?.answer(&games)?, bags);
+        assert_eq!(
+            BagQuery::from_str("likely-48")?.answer(&games)?,
+            [smallest_bag(&games)]
+        );
+        assert!(BagQuery::from_str("likely-47")?.answer(&games)?.is_empty());
+        assert!(BagQuery::from_str("largest-3").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let all_ids =
+            |input: &str| parse_input(input).map(|games| games.iter().map(|game| game.id).sum());
+        let res = minimize::<Game>(
+            "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+            |input| calculate_result(input, PuzzlePart::One).ok() != all_ids(input).ok(),
+        )?;
+        assert_eq!(
+            res,
+            "Game 3: 20 red, 8 green, 6 blue; 4 red, 13 green, 5 blue; 1 red, 5 green"
+        );
+
+        Ok(())
+    }
+
+    fn cube_set_strategy() -> impl Strategy<Value = CubeSet> {
+        prop::collection::btree_map("red|green|blue|[a-z]{1,8}", 0..=20u32, 0..5)
+            .prop_map(CubeSet::from_iter)
+    }
+
+    fn game_strategy() -> impl Strategy<Value = Game> {
+        (
+            1..=1000u32,
+            prop::collection::vec(cube_set_strategy(), 1..8),
+        )
+            .prop_map(|(id, cube_sets)| Game { id, cube_sets })
+    }
+
+    proptest! {
+        #[test]
+        fn test_game_parse_roundtrip(game in game_strategy()) {
+            let rendered = game.to_string();
+            prop_assert_eq!(Game::from_str(&rendered).ok(), Some(game));
+        }
+
+        #[test]
+        fn test_cube_set_parse_roundtrip(cube_set in cube_set_strategy()) {
+            let rendered = cube_set.to_string();
+            prop_assert_eq!(CubeSet::from_str(&rendered).ok(), Some(cube_set));
+        }
+
+        #[test]
+        fn test_game_parse_never_panics(input in "Game [0-9]{0,12}: [0-9a-z ,;]{0,64}|\\PC*") {
+            let _ = Game::from_str(&input);
+        }
+    }
+}
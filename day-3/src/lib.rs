@@ -0,0 +1,681 @@
+use aoc_2023_common::explain::{Highlight, Step};
+use aoc_2023_common::input::try_fold_lines;
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::svg::{color, Style, Svg};
+use aoc_2023_common::PuzzlePart;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+use tracing::instrument;
+
+use eyre::ContextCompat;
+
+use std::{iter, vec};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Schematic {
+    pub objects: Vec<Object>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Object {
+    Number(Number),
+    Symbol(Symbol),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Number {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub num: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Symbol {
+    pub x: usize,
+    pub y: usize,
+    pub symbol: char,
+}
+
+impl Schematic {
+    pub fn numbers(&self) -> impl Iterator<Item = Number> + '_ {
+        self.objects.iter().filter_map(|obj| match obj {
+            Object::Number(number) => Some(*number),
+            _ => None,
+        })
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.objects.iter().filter_map(|obj| match obj {
+            Object::Symbol(symbol) => Some(*symbol),
+            _ => None,
+        })
+    }
+
+    pub fn adjacent_symbols(&self, num: Number) -> impl Iterator<Item = Symbol> + '_ {
+        self.symbols().filter(move |sym| num.is_adjacent_to(*sym))
+    }
+}
+
+impl Number {
+    pub fn is_adjacent_to(self, sym: Symbol) -> bool {
+        let Number { x, y, width, .. } = self;
+
+        let in_adjacent_row = (sym.y as isize - y as isize).abs() <= 1;
+        let in_adjacent_column = sym.x as isize >= (x as isize) - 1 && sym.x <= (x + width);
+
+        in_adjacent_row && in_adjacent_column
+    }
+
+    fn covers(self, x: usize, y: usize) -> bool {
+        y == self.y && (self.x..self.x + self.width).contains(&x)
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = eyre::Error;
+
+    #[instrument(level = "debug", skip_all)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let objects = s
+            .lines()
+            .enumerate()
+            .map(|(y, line)| parse_row(y, line))
+            .collect::<eyre::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(Schematic { objects })
+    }
+}
+
+/// Parses the objects of the row `y` of a schematic.
+fn parse_row(y: usize, line: &str) -> eyre::Result<Vec<Object>> {
+    enum ParseState {
+        Number { num: u32, width: usize },
+        Other,
+    }
+
+    let (parsed, _) = line
+        .chars()
+        .chain(iter::once('.')) // chain a '.' for flushing the last parse state
+        .enumerate()
+        .try_fold(
+            (vec![], ParseState::Other),
+            |(mut parsed, state), (x, c)| {
+                let next_state = if c.is_ascii_digit() {
+                    let digit = c.to_digit(10).expect("must be digit");
+
+                    match state {
+                        ParseState::Number { num, width } => ParseState::Number {
+                            num: num
+                                .checked_mul(10)
+                                .and_then(|num| num.checked_add(digit))
+                                .wrap_err_with(|| format!("number too large at ({x}, {y})"))?,
+                            width: width + 1,
+                        },
+                        ParseState::Other => ParseState::Number {
+                            num: digit,
+                            width: 1,
+                        },
+                    }
+                } else {
+                    if let ParseState::Number { num, width } = state {
+                        parsed.push(Object::Number(Number {
+                            x: x - width,
+                            y,
+                            width,
+                            num,
+                        }));
+                    }
+
+                    if c != '.' {
+                        parsed.push(Object::Symbol(Symbol { x, y, symbol: c }));
+                    }
+
+                    ParseState::Other
+                };
+
+                Ok::<_, eyre::Error>((parsed, next_state))
+            },
+        )?;
+
+    Ok(parsed)
+}
+
+impl fmt::Display for Schematic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows: Vec<Vec<char>> = vec![];
+        for object in &self.objects {
+            let (x, y, text) = match *object {
+                Object::Number(Number { x, y, width, num }) => (x, y, format!("{num:0width$}")),
+                Object::Symbol(Symbol { x, y, symbol }) => (x, y, symbol.to_string()),
+            };
+
+            if rows.len() <= y {
+                rows.resize(y + 1, vec![]);
+            }
+            let row = &mut rows[y];
+            let end = x + text.chars().count();
+            if row.len() < end {
+                row.resize(end, '.');
+            }
+            row.splice(x..end, text.chars());
+        }
+
+        // pad every row to the same width, like the puzzle input
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for (y, row) in rows.iter_mut().enumerate() {
+            row.resize(width, '.');
+
+            if y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<u32> {
+    calculate_result_from_reader(normalize(input).as_bytes(), puzzle_part)
+}
+
+/// Same as [`calculate_result`], but reads the schematic row by row. Objects only touch the rows
+/// right above and below them, so only a window of three rows is kept in memory.
+#[instrument(name = "calculate_result", skip(reader))]
+pub fn calculate_result_from_reader(
+    reader: impl BufRead,
+    puzzle_part: PuzzlePart,
+) -> eyre::Result<u32> {
+    // starts with the empty row above the schematic
+    let mut window = VecDeque::from([vec![]]);
+    let (sum, rows) = try_fold_lines(reader, (0, 0), |(sum, y), line| {
+        window.push_back(parse_row(y, line)?);
+        Ok((sum + sum_middle_row(&mut window, y, puzzle_part), y + 1))
+    })?;
+    // flush the last row with the empty row below the schematic
+    window.push_back(vec![]);
+
+    Ok(sum + sum_middle_row(&mut window, rows, puzzle_part))
+}
+
+/// Once the window holds three rows, sums up the middle one and drops the top one.
+fn sum_middle_row(
+    window: &mut VecDeque<Vec<Object>>,
+    bottom_y: usize,
+    puzzle_part: PuzzlePart,
+) -> u32 {
+    if window.len() < 3 {
+        return 0;
+    }
+
+    let schematic = Schematic {
+        objects: window.iter().flatten().copied().collect(),
+    };
+    window.pop_front();
+
+    row_sum(&schematic, bottom_y - 1, puzzle_part)
+}
+
+/// Sums up the part numbers (or gear ratios) of row `y`, which needs the rows next to it as well.
+fn row_sum(schematic: &Schematic, y: usize, puzzle_part: PuzzlePart) -> u32 {
+    match puzzle_part {
+        PuzzlePart::One => schematic
+            .numbers()
+            .filter(|num| num.y == y)
+            .filter(|num| schematic.adjacent_symbols(*num).next().is_some())
+            .map(|num| num.num)
+            .sum(),
+        PuzzlePart::Two => {
+            let symbol_to_adjacent_numbers = schematic
+                .numbers()
+                .flat_map(|num| {
+                    schematic
+                        .adjacent_symbols(num)
+                        .filter(|sym| sym.symbol == '*' && sym.y == y)
+                        .map(move |sym| (sym, num))
+                })
+                .fold(HashMap::<_, Vec<_>>::new(), |mut acc, (sym, num)| {
+                    acc.entry(sym).or_default().push(num);
+                    acc
+                });
+
+            symbol_to_adjacent_numbers
+                .into_values()
+                .filter_map(|nums| match nums.as_slice() {
+                    &[num1, num2] => Some(num1.num * num2.num),
+                    _ => None,
+                })
+                .sum()
+        }
+    }
+}
+
+/// Rows of the schematic shown above and below the object a step is about.
+const EXPLAIN_CONTEXT_ROWS: usize = 2;
+
+/// Steps through the numbers of the schematic for puzzle part one and through the gears for
+/// puzzle part two, highlighting what they're adjacent to.
+pub fn explain(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Vec<Step>> {
+    let input = normalize(input);
+    let schematic = Schematic::from_str(&input)?;
+    let rows = input.lines().collect::<Vec<_>>();
+    let context = |y: usize| y.saturating_sub(EXPLAIN_CONTEXT_ROWS)..=y + EXPLAIN_CONTEXT_ROWS;
+
+    let mut sum = 0;
+    let steps = match puzzle_part {
+        PuzzlePart::One => schematic
+            .numbers()
+            .map(|num| {
+                let symbols = schematic.adjacent_symbols(num).collect::<Vec<_>>();
+                let caption = if symbols.is_empty() {
+                    format!(
+                        "{} at ({}, {}) isn't adjacent to a symbol, the sum stays at {sum}",
+                        num.num, num.x, num.y
+                    )
+                } else {
+                    sum += num.num;
+                    format!(
+                        "{} at ({}, {}) is a part number, the sum is now {sum}",
+                        num.num, num.x, num.y
+                    )
+                };
+
+                Step::new(caption).grid_lines(&rows, context(num.y), |x, y| {
+                    if num.covers(x, y) {
+                        Highlight::Focus
+                    } else if symbols.iter().any(|sym| (sym.x, sym.y) == (x, y)) {
+                        Highlight::Match
+                    } else {
+                        Highlight::None
+                    }
+                })
+            })
+            .collect(),
+        PuzzlePart::Two => schematic
+            .symbols()
+            .filter(|sym| sym.symbol == '*')
+            .map(|sym| {
+                let nums = schematic
+                    .numbers()
+                    .filter(|num| num.is_adjacent_to(sym))
+                    .collect::<Vec<_>>();
+                let caption = match nums.as_slice() {
+                    &[num1, num2] => {
+                        sum += num1.num * num2.num;
+                        format!(
+                            "gear at ({}, {}): {} * {} = {}, the sum is now {sum}",
+                            sym.x,
+                            sym.y,
+                            num1.num,
+                            num2.num,
+                            num1.num * num2.num
+                        )
+                    }
+                    _ => format!(
+                        "* at ({}, {}) is adjacent to {} numbers, so it isn't a gear",
+                        sym.x,
+                        sym.y,
+                        nums.len()
+                    ),
+                };
+
+                Step::new(caption).grid_lines(&rows, context(sym.y), |x, y| {
+                    if (sym.x, sym.y) == (x, y) {
+                        Highlight::Focus
+                    } else if nums.iter().any(|num| num.covers(x, y)) {
+                        Highlight::Match
+                    } else {
+                        Highlight::None
+                    }
+                })
+            })
+            .collect(),
+    };
+
+    Ok(steps)
+}
+
+/// Size of a schematic cell in the picture.
+const SVG_CELL: f64 = 14.0;
+
+/// Draws the schematic with the part numbers highlighted, and for puzzle part two the gears along
+/// with their numbers.
+pub fn render_svg(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Svg> {
+    let input = normalize(input);
+    let schematic = Schematic::from_str(&input)?;
+    let rows = input.lines().collect::<Vec<_>>();
+    let width = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut svg = Svg::new(width as f64 * SVG_CELL, rows.len() as f64 * SVG_CELL);
+    let cell = |x: usize, y: usize| (x as f64 * SVG_CELL, y as f64 * SVG_CELL);
+
+    let gears = schematic
+        .symbols()
+        .filter(|symbol| puzzle_part == PuzzlePart::Two && symbol.symbol == '*')
+        .filter(|&symbol| {
+            schematic
+                .numbers()
+                .filter(|number| number.is_adjacent_to(symbol))
+                .count()
+                == 2
+        })
+        .collect::<Vec<_>>();
+
+    for number in schematic.numbers() {
+        let highlight = if gears.iter().any(|&gear| number.is_adjacent_to(gear)) {
+            Highlight::Focus
+        } else if schematic.adjacent_symbols(number).next().is_some() {
+            Highlight::Match
+        } else {
+            Highlight::Dim
+        };
+        let (x, y) = cell(number.x, number.y);
+        svg.rect(
+            x,
+            y,
+            number.width as f64 * SVG_CELL,
+            SVG_CELL,
+            &Style::new().fill(color(highlight)).opacity(0.4),
+        );
+    }
+    for gear in &gears {
+        let (x, y) = cell(gear.x, gear.y);
+        svg.circle(
+            (x + SVG_CELL / 2.0, y + SVG_CELL / 2.0),
+            SVG_CELL / 2.0,
+            &Style::new().stroke(color(Highlight::Focus), 2.0),
+        );
+    }
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate().filter(|&(_, c)| c != '.') {
+            let (x, y) = cell(x, y);
+            svg.text(
+                (x + SVG_CELL / 2.0, y + SVG_CELL * 0.75),
+                &c.to_string(),
+                &Style::new().font_size(SVG_CELL * 0.8).centered(),
+            );
+        }
+    }
+
+    Ok(svg)
+}
+
+impl InputPart for Object {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        Ok(Schematic::from_str(input)?.objects)
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        Schematic {
+            objects: parts.to_vec(),
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(res, 4361);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(res, 467835);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schematic_parse() -> eyre::Result<()> {
+        let res = Schematic::from_str(
+            "\
+467..114..
+...*.....1
+",
+        )?;
+        assert_eq!(
+            res,
+            Schematic {
+                objects: vec![
+                    Object::Number(Number {
+                        x: 0,
+                        y: 0,
+                        width: 3,
+                        num: 467,
+                    }),
+                    Object::Number(Number {
+                        x: 5,
+                        y: 0,
+                        width: 3,
+                        num: 114
+                    }),
+                    Object::Symbol(Symbol {
+                        x: 3,
+                        y: 1,
+                        symbol: '*',
+                    }),
+                    Object::Number(Number {
+                        x: 9,
+                        y: 1,
+                        width: 1,
+                        num: 1
+                    })
+                ]
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn schematic_adjacent_symbols() -> eyre::Result<()> {
+        let schematic = Schematic::from_str(
+            "\
+*...../
+.467+./
+....-./
+",
+        )?;
+        let num = schematic.numbers().next().unwrap();
+        let res = schematic.adjacent_symbols(num).collect::<Vec<_>>();
+        assert_eq!(
+            res,
+            vec![
+                Symbol {
+                    x: 0,
+                    y: 0,
+                    symbol: '*',
+                },
+                Symbol {
+                    x: 4,
+                    y: 1,
+                    symbol: '+',
+                },
+                Symbol {
+                    x: 4,
+                    y: 2,
+                    symbol: '-',
+                }
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schematic_display() -> eyre::Result<()> {
+        let schematic = Schematic::from_str(
+            "\
+467..114..
+...*.....1
+",
+        )?;
+
+        assert_eq!(schematic.to_string(), "467..114..\n...*.....1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_from_reader() -> eyre::Result<()> {
+        // a tiny buffer makes lines span several reads
+        let reader = BufReader::with_capacity(4, EXAMPLE.as_bytes());
+        assert_eq!(
+            calculate_result_from_reader(reader, PuzzlePart::Two)?,
+            467835
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_svg() -> eyre::Result<()> {
+        let svg = render_svg(EXAMPLE, PuzzlePart::Two)?.to_string();
+
+        // 10 numbers, of which 4 are next to the 2 gears and 8 are part numbers
+        assert_eq!(svg.matches("<rect x=").count(), 10);
+        assert_eq!(svg.matches(r##"fill="#e6a700" opacity"##).count(), 4);
+        assert_eq!(svg.matches(r##"fill="#2e9e44" opacity"##).count(), 4);
+        assert_eq!(svg.matches("<circle").count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let steps = explain(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(steps.len(), 10);
+        assert_eq!(
+            steps[1].to_string(),
+            "114 at (5, 0) isn't adjacent to a symbol, the sum stays at 467\n\
+             467..[114]..\n\
+             ...*......\n\
+             ..35..633."
+        );
+
+        let steps = explain(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            steps[0].to_string(),
+            "gear at (3, 1): 467 * 35 = 16345, the sum is now 16345\n\
+             <467>..114..\n\
+             ...[*]......\n\
+             ..<35>..633.\n\
+             ......#..."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<Object>(EXAMPLE, |input| {
+            calculate_result(input, PuzzlePart::Two).is_ok_and(|res| res > 0)
+        })?;
+        assert_eq!(res, "467.\n...*\n..35");
+
+        Ok(())
+    }
+
+    fn schematic_strategy() -> impl Strategy<Value = Schematic> {
+        let object = prop_oneof![
+            (0..100_000u32).prop_map(|num| Object::Number(Number {
+                x: 0,
+                y: 0,
+                width: num.to_string().len(),
+                num,
+            })),
+            prop::sample::select(vec!['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'])
+                .prop_map(|symbol| Object::Symbol(Symbol { x: 0, y: 0, symbol })),
+        ];
+        let row = prop::collection::vec((0..4usize, object), 0..8);
+
+        prop::collection::vec(row, 0..8).prop_map(|rows| {
+            let mut objects = vec![];
+            for (y, row) in rows.into_iter().enumerate() {
+                let mut x = 0;
+                let mut previous_was_number = false;
+                for (gap, mut object) in row {
+                    // adjacent numbers would be parsed as a single number
+                    x += if previous_was_number { gap.max(1) } else { gap };
+
+                    match &mut object {
+                        Object::Number(number) => {
+                            (number.x, number.y) = (x, y);
+                            x += number.width;
+                            previous_was_number = true;
+                        }
+                        Object::Symbol(symbol) => {
+                            (symbol.x, symbol.y) = (x, y);
+                            x += 1;
+                            previous_was_number = false;
+                        }
+                    }
+
+                    objects.push(object);
+                }
+            }
+
+            Schematic { objects }
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_schematic_parse_roundtrip(schematic in schematic_strategy()) {
+            let rendered = schematic.to_string();
+            prop_assert_eq!(Schematic::from_str(&rendered).ok(), Some(schematic));
+        }
+
+        #[test]
+        fn test_row_window_matches_whole_schematic(schematic in schematic_strategy()) {
+            let rendered = schematic.to_string();
+            let whole_schematic = (0..rendered.lines().count())
+                .map(|y| row_sum(&schematic, y, PuzzlePart::One))
+                .sum::<u32>();
+
+            prop_assert_eq!(calculate_result(&rendered, PuzzlePart::One).ok(), Some(whole_schematic));
+        }
+
+        #[test]
+        fn test_schematic_parse_never_panics(input in "([0-9.*#+]{0,40}\n){0,8}|\\PC*") {
+            let _ = Schematic::from_str(&input);
+        }
+    }
+}
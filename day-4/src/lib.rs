@@ -0,0 +1,260 @@
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::PuzzlePart;
+use tracing::{debug_span, instrument};
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use eyre::{ContextCompat, WrapErr};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub id: usize,
+    pub winning: HashSet<u32>,
+    pub guessed: HashSet<u32>,
+}
+
+impl FromStr for Card {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static CARD_REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"Card +(?<card_id>\d+): (?<winning>.+) \| (?<guessed>.+)").unwrap()
+        });
+
+        let card_captures = CARD_REGEX
+            .captures(s)
+            .wrap_err("card pattern didn't match")?;
+
+        let card_id_match = card_captures
+            .name("card_id")
+            .wrap_err("invalid capture group name")?;
+        let winning_numbers_match = card_captures
+            .name("winning")
+            .wrap_err("invalid capture group name")?;
+        let guessed_numbers_match = card_captures
+            .name("guessed")
+            .wrap_err("invalid capture group name")?;
+
+        let card_id = usize::from_str(card_id_match.as_str())?;
+        let winning_numbers = winning_numbers_match
+            .as_str()
+            .split(' ')
+            .filter(|str| !str.is_empty())
+            .map(u32::from_str)
+            .map(|res| res.wrap_err("invalid number"))
+            .collect::<eyre::Result<HashSet<_>>>()?;
+        let guessed_numbers = guessed_numbers_match
+            .as_str()
+            .split(' ')
+            .filter(|str| !str.is_empty())
+            .map(u32::from_str)
+            .map(|res| res.wrap_err("invalid number"))
+            .collect::<eyre::Result<HashSet<_>>>()?;
+
+        Ok(Self {
+            id: card_id,
+            winning: winning_numbers,
+            guessed: guessed_numbers,
+        })
+    }
+}
+
+impl Card {
+    /// How many of the guessed numbers are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.guessed.intersection(&self.winning).count()
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_numbers(f: &mut fmt::Formatter<'_>, numbers: &HashSet<u32>) -> fmt::Result {
+            let mut numbers = numbers.iter().collect::<Vec<_>>();
+            numbers.sort_unstable();
+
+            for (idx, number) in numbers.into_iter().enumerate() {
+                if idx > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{number:>2}")?;
+            }
+
+            Ok(())
+        }
+
+        write!(f, "Card {:>3}: ", self.id)?;
+        write_numbers(f, &self.winning)?;
+        write!(f, " | ")?;
+        write_numbers(f, &self.guessed)
+    }
+}
+
+#[instrument(skip(input))]
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<u32> {
+    let cards = parse_input(&normalize(input))?;
+    let _solve = debug_span!("solve").entered();
+
+    let res = match puzzle_part {
+        PuzzlePart::One => cards
+            .iter()
+            .map(|card| match card.matches() {
+                0 => 0,
+                matches => 1 << (matches - 1),
+            })
+            .sum(),
+        PuzzlePart::Two => {
+            let mut card_amount = cards
+                .iter()
+                .map(|card| (card.id, 1usize))
+                .collect::<BTreeMap<_, _>>();
+            for card in &cards {
+                let matches = card.matches();
+                let amount = card_amount[&card.id];
+                for i in 1..=matches {
+                    if let Some(old_amount) = card_amount.get_mut(&(card.id + i)) {
+                        *old_amount += amount;
+                    }
+                }
+            }
+
+            card_amount.values().map(|count| *count as u32).sum()
+        }
+    };
+
+    Ok(res)
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input(input: &str) -> eyre::Result<Vec<Card>> {
+    input.lines().map(Card::from_str).collect()
+}
+
+impl InputPart for Card {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        parse_input(input)
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        parts
+            .iter()
+            .map(Card::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::assert_snapshot;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(res, 13);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(res, 30);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_parse() -> eyre::Result<()> {
+        let res = Card::from_str("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53")?;
+        assert_eq!(
+            res,
+            Card {
+                id: 1,
+                winning: HashSet::from([41, 48, 83, 86, 17]),
+                guessed: HashSet::from([83, 86, 6, 31, 17, 9, 48, 53]),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_display() -> eyre::Result<()> {
+        let card = Card::from_str("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53")?;
+
+        assert_eq!(
+            card.to_string(),
+            "Card   1: 17 41 48 83 86 |  6  9 17 31 48 53 83 86"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<Card>(EXAMPLE, |input| {
+            calculate_result(input, PuzzlePart::One).is_ok_and(|res| res > 0)
+        })?;
+        assert_eq!(res, "Card   1: 17 41 48 83 86 |  6  9 17 31 48 53 83 86");
+
+        Ok(())
+    }
+
+    fn card_strategy() -> impl Strategy<Value = Card> {
+        (
+            1..=1000usize,
+            prop::collection::hash_set(0..100u32, 1..10),
+            prop::collection::hash_set(0..100u32, 1..25),
+        )
+            .prop_map(|(id, winning, guessed)| Card {
+                id,
+                winning,
+                guessed,
+            })
+    }
+
+    #[test]
+    fn test_snapshot_matches() -> eyre::Result<()> {
+        let cards = parse_input(include_str!("input.txt"))?;
+        let matches = cards
+            .iter()
+            .map(|card| match card.matches() {
+                1 => format!("Card {:>3}: 1 match", card.id),
+                matches => format!("Card {:>3}: {matches} matches", card.id),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_snapshot!("matches", matches)
+    }
+
+    proptest! {
+        #[test]
+        fn test_card_parse_roundtrip(card in card_strategy()) {
+            let rendered = card.to_string();
+            prop_assert_eq!(Card::from_str(&rendered).ok(), Some(card));
+        }
+
+        #[test]
+        fn test_card_parse_never_panics(input in "Card +[0-9]{0,24}: [0-9 |]{0,64}|\\PC*") {
+            let _ = Card::from_str(&input);
+        }
+    }
+}
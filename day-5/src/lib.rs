@@ -0,0 +1,1010 @@
+use aoc_2023_common::differential::Solutions;
+use aoc_2023_common::explain::{Highlight, Span, Step};
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::svg::{color, Style, Svg};
+use aoc_2023_common::PuzzlePart;
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::str::FromStr;
+use std::{fmt, iter};
+
+use eyre::{bail, ContextCompat, WrapErr};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::{debug_span, instrument};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    Seed,
+    Soil,
+    Fertilizer,
+    Water,
+    Light,
+    Temperature,
+    Humidity,
+    Location,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    pub source_range: u64,
+    pub destination_range: u64,
+    pub len: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub conversions: HashMap<Category, Vec<Mapping>>,
+}
+
+impl Almanac {
+    pub fn convert_seed_to_location(&self, seed_number: u64) -> Option<u64> {
+        let mut category = Category::Seed;
+        let mut number = seed_number;
+
+        loop {
+            let (new_category, new_number) = self.convert(category, number)?;
+
+            if new_category == Category::Location {
+                return Some(new_number);
+            }
+
+            category = new_category;
+            number = new_number;
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn convert(
+        &self,
+        source_category: Category,
+        source_number: u64,
+    ) -> Option<(Category, u64)> {
+        let new_category = source_category.next()?;
+        let mappings = self.conversions.get(&source_category)?;
+
+        let new_number = mappings
+            .iter()
+            .copied()
+            .find_map(|mapping| {
+                let Mapping {
+                    source_range,
+                    destination_range,
+                    len,
+                } = mapping;
+
+                if (source_range..source_range + len).contains(&source_number) {
+                    Some(destination_range + (source_number - source_range))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(source_number);
+
+        Some((new_category, new_number))
+    }
+
+    pub fn convert_seed_range_to_location(
+        &self,
+        seed_range: Range<u64>,
+    ) -> Option<Vec<Range<u64>>> {
+        let mut category = Category::Seed;
+        let mut ranges = vec![seed_range];
+
+        loop {
+            let (new_category, new_ranges) = self.convert_ranges(category, ranges)?;
+
+            if new_category == Category::Location {
+                return Some(new_ranges);
+            }
+
+            category = new_category;
+            ranges = new_ranges;
+        }
+    }
+
+    /// Same as [`Almanac::convert`], but converts whole ranges of numbers at once by splitting
+    /// them at the mapping boundaries.
+    #[instrument(level = "trace", skip(self, source_ranges), fields(ranges = source_ranges.len()))]
+    pub fn convert_ranges(
+        &self,
+        source_category: Category,
+        source_ranges: Vec<Range<u64>>,
+    ) -> Option<(Category, Vec<Range<u64>>)> {
+        let new_category = source_category.next()?;
+        let mappings = self.conversions.get(&source_category)?;
+
+        let mut unmapped = source_ranges;
+        let mut mapped = vec![];
+        // the first mapping containing a number wins, so only pass on what's left unmapped
+        for mapping in mappings {
+            let Mapping {
+                source_range,
+                destination_range,
+                len,
+            } = *mapping;
+
+            unmapped = unmapped
+                .into_iter()
+                .flat_map(|range| {
+                    let overlap = range.start.max(source_range)..range.end.min(source_range + len);
+                    if overlap.is_empty() {
+                        return vec![range];
+                    }
+
+                    mapped.push(
+                        destination_range + (overlap.start - source_range)
+                            ..destination_range + (overlap.end - source_range),
+                    );
+                    [range.start..overlap.start, overlap.end..range.end]
+                        .into_iter()
+                        .filter(|rest| !rest.is_empty())
+                        .collect()
+                })
+                .collect();
+        }
+        mapped.extend(unmapped);
+
+        Some((new_category, mapped))
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = eyre::Error;
+
+    #[instrument(level = "debug", skip_all)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        let (_, seeds_str) = lines
+            .next()
+            .wrap_err("expect seeds string")?
+            .split_once(' ')
+            .wrap_err("invalid format for seeds string")?;
+
+        let seeds = seeds_str
+            .split(' ')
+            .map(u64::from_str)
+            .map(|res| res.wrap_err("failed to parse seed number"))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        static CONVERSION_HEADING_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?<source>.+)-to-(?<destination>.+) map:").unwrap());
+        static CONVERSION_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?<destination>\d+) (?<source>\d+) (?<len>\d+)").unwrap());
+
+        let (_, conversions) = lines.filter(|line| !line.trim().is_empty()).try_fold(
+            (None, HashMap::new()),
+            |(cur_source, mut conversions), line| {
+                if let Some(heading_captures) = CONVERSION_HEADING_REGEX.captures(line) {
+                    let source_category: Category = heading_captures
+                        .name("source")
+                        .wrap_err("invalid regex group")?
+                        .as_str()
+                        .parse()?;
+                    let destination_category: Category = heading_captures
+                        .name("destination")
+                        .wrap_err("invalid regex group")?
+                        .as_str()
+                        .parse()?;
+
+                    match source_category.next() {
+                        Some(next_category) if next_category != destination_category => bail!(
+                            "next category for {source_category:?} not {destination_category:?}"
+                        ),
+                        None => bail!("final category is input"),
+                        _ => return Ok((Some(source_category), conversions)),
+                    }
+                }
+
+                let Some(cur_source) = cur_source else {
+                    bail!("no mapping active for line {line}");
+                };
+
+                let Some(conversion_captures) = CONVERSION_REGEX.captures(line) else {
+                    bail!("invalid line: {line}");
+                };
+
+                let source_range_start: u64 = conversion_captures
+                    .name("source")
+                    .wrap_err("invalid regex group")?
+                    .as_str()
+                    .parse()?;
+                let destination_range_start: u64 = conversion_captures
+                    .name("destination")
+                    .wrap_err("invalid regex group")?
+                    .as_str()
+                    .parse()?;
+                let range_len: u64 = conversion_captures
+                    .name("len")
+                    .wrap_err("invalid regex group")?
+                    .as_str()
+                    .parse()?;
+
+                let mappings: &mut Vec<_> = conversions.entry(cur_source).or_default();
+                mappings.push(Mapping {
+                    source_range: source_range_start,
+                    destination_range: destination_range_start,
+                    len: range_len,
+                });
+
+                Ok((Some(cur_source), conversions))
+            },
+        )?;
+
+        Ok(Self { seeds, conversions })
+    }
+}
+
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for seed in &self.seeds {
+            write!(f, " {seed}")?;
+        }
+        writeln!(f)?;
+
+        let source_categories = iter::successors(Some(Category::Seed), |category| category.next());
+        for source_category in source_categories {
+            let (Some(destination_category), Some(mappings)) = (
+                source_category.next(),
+                self.conversions.get(&source_category),
+            ) else {
+                continue;
+            };
+
+            write!(f, "\n{source_category}-to-{destination_category} map:")?;
+            for mapping in mappings {
+                write!(f, "\n{mapping}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.destination_range, self.source_range, self.len
+        )
+    }
+}
+
+impl Category {
+    pub fn next(self) -> Option<Category> {
+        let next = match self {
+            Self::Seed => Self::Soil,
+            Self::Soil => Self::Fertilizer,
+            Self::Fertilizer => Self::Water,
+            Self::Water => Self::Light,
+            Self::Light => Self::Temperature,
+            Self::Temperature => Self::Humidity,
+            Self::Humidity => Self::Location,
+            Self::Location => return None,
+        };
+
+        Some(next)
+    }
+}
+
+impl FromStr for Category {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let category = match s {
+            "seed" => Self::Seed,
+            "soil" => Self::Soil,
+            "fertilizer" => Self::Fertilizer,
+            "water" => Self::Water,
+            "light" => Self::Light,
+            "temperature" => Self::Temperature,
+            "humidity" => Self::Humidity,
+            "location" => Self::Location,
+            _ => bail!("unknown category: {s}"),
+        };
+
+        Ok(category)
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Seed => "seed",
+            Self::Soil => "soil",
+            Self::Fertilizer => "fertilizer",
+            Self::Water => "water",
+            Self::Light => "light",
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+            Self::Location => "location",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[instrument(skip(input))]
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<u64> {
+    let almanac = Almanac::from_str(&normalize(input))?;
+    let _solve = debug_span!("solve").entered();
+
+    let seed_ranges = match puzzle_part {
+        PuzzlePart::One => almanac
+            .seeds
+            .iter()
+            .map(|&seed_number| seed_number..seed_number + 1)
+            .collect::<Vec<_>>(),
+        PuzzlePart::Two => almanac
+            .seeds
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&[seed_range_start, seed_range_len]| {
+                seed_range_start..seed_range_start + seed_range_len
+            })
+            .collect(),
+    };
+
+    let res = seed_ranges
+        .into_iter()
+        .filter_map(|seed_range| almanac.convert_seed_range_to_location(seed_range))
+        .flatten()
+        .filter(|location_range| !location_range.is_empty())
+        .map(|location_range| location_range.start)
+        .min()
+        .unwrap_or(0);
+
+    Ok(res)
+}
+
+pub fn solutions() -> Solutions<u64> {
+    Solutions::new()
+        .register(PuzzlePart::One, "seed ranges", |input| {
+            calculate_result(input, PuzzlePart::One)
+        })
+        .register(PuzzlePart::One, "single seeds", |input| {
+            calculate_result_per_seed(input, PuzzlePart::One)
+        })
+        .register(PuzzlePart::Two, "seed ranges", |input| {
+            calculate_result(input, PuzzlePart::Two)
+        })
+        .register(PuzzlePart::Two, "single seeds", |input| {
+            calculate_result_per_seed(input, PuzzlePart::Two)
+        })
+}
+
+/// Converts every seed on its own, which is way too slow for the ranges of puzzle part two.
+#[instrument(skip(input))]
+fn calculate_result_per_seed(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<u64> {
+    let almanac = Almanac::from_str(&normalize(input))?;
+    let _solve = debug_span!("solve").entered();
+
+    let res = match puzzle_part {
+        PuzzlePart::One => almanac
+            .seeds
+            .iter()
+            .copied()
+            .filter_map(|seed_number| almanac.convert_seed_to_location(seed_number))
+            .min()
+            .unwrap_or(0),
+        PuzzlePart::Two => almanac
+            .seeds
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .copied()
+            .flat_map(|[seed_range_start, seed_range_len]| {
+                seed_range_start..seed_range_start + seed_range_len
+            })
+            .filter_map(|seed_number| almanac.convert_seed_to_location(seed_number))
+            .min()
+            .unwrap_or(0),
+    };
+
+    Ok(res)
+}
+
+/// Steps through the path of every seed (puzzle part one) or seed range (puzzle part two) through
+/// the categories, showing the mapping used for each conversion.
+pub fn explain(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Vec<Step>> {
+    let almanac = Almanac::from_str(&normalize(input))?;
+    let mut lowest = None::<u64>;
+
+    let steps = match puzzle_part {
+        PuzzlePart::One => almanac
+            .seeds
+            .iter()
+            .map(|&seed_number| {
+                let (mut category, mut number) = (Category::Seed, seed_number);
+                let mut lines = vec![];
+                while let Some((next_category, next_number)) = almanac.convert(category, number) {
+                    let mapping = almanac.conversions.get(&category).and_then(|mappings| {
+                        mappings.iter().find(|mapping| {
+                            (mapping.source_range..mapping.source_range + mapping.len)
+                                .contains(&number)
+                        })
+                    });
+                    lines.push(vec![
+                        Span::plain(format!("{category} {number} -> ")),
+                        Span::highlighted(
+                            format!("{next_category} {next_number}"),
+                            Highlight::Focus,
+                        ),
+                        match mapping {
+                            Some(mapping) => Span::highlighted(
+                                format!("  (mapping {mapping})"),
+                                Highlight::Match,
+                            ),
+                            None => Span::highlighted("  (not mapped)", Highlight::Dim),
+                        },
+                    ]);
+                    (category, number) = (next_category, next_number);
+                }
+
+                if category == Category::Location {
+                    lowest = Some(lowest.map_or(number, |lowest| lowest.min(number)));
+                }
+                let caption = format!(
+                    "seed {seed_number} ends up at {category} {number}, the lowest location is {}",
+                    lowest.map_or("unknown".to_string(), |lowest| lowest.to_string())
+                );
+
+                Step { caption, lines }
+            })
+            .collect(),
+        PuzzlePart::Two => almanac
+            .seeds
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&[start, len]| {
+                let mut category = Category::Seed;
+                let mut ranges = iter::once(start..start + len).collect::<Vec<_>>();
+                let format_ranges = |ranges: &[Range<u64>]| {
+                    ranges
+                        .iter()
+                        .map(|range| format!("{}..{}", range.start, range.end))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+
+                let mut step =
+                    Step::new("").plain_line(format!("{category}: {}", format_ranges(&ranges)));
+                while let Some((next_category, next_ranges)) =
+                    almanac.convert_ranges(category, ranges.clone())
+                {
+                    step = step.line(vec![
+                        Span::plain(format!("{next_category}: ")),
+                        Span::highlighted(format_ranges(&next_ranges), Highlight::Focus),
+                    ]);
+                    (category, ranges) = (next_category, next_ranges);
+                }
+
+                let first = ranges
+                    .iter()
+                    .filter(|range| !range.is_empty())
+                    .map(|range| range.start)
+                    .min();
+                if let (Category::Location, Some(first)) = (category, first) {
+                    lowest = Some(lowest.map_or(first, |lowest| lowest.min(first)));
+                }
+                step.caption = format!(
+                    "seeds {start}..{} end up in {} {category} ranges, the lowest location is {}",
+                    start + len,
+                    ranges.len(),
+                    lowest.map_or("unknown".to_string(), |lowest| lowest.to_string())
+                );
+
+                step
+            })
+            .collect(),
+    };
+
+    Ok(steps)
+}
+
+/// Width of the bars a category's numbers are drawn on.
+const SVG_BAR_WIDTH: f64 = 800.0;
+const SVG_BAR_HEIGHT: f64 = 24.0;
+/// Vertical space between two categories, where the mappings are drawn.
+const SVG_GAP: f64 = 48.0;
+/// Space left of the bars for the category names.
+const SVG_LABEL_WIDTH: f64 = 110.0;
+const SVG_PALETTE: [&str; 6] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#b07aa1",
+];
+
+/// Draws every category as a bar of all its numbers, each mapping as a band from the numbers it
+/// maps to the numbers they're mapped to, and the seeds (ranges for puzzle part two) on top as
+/// they pass through the categories.
+pub fn render_svg(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Svg> {
+    let almanac = Almanac::from_str(&normalize(input))?;
+    let categories =
+        iter::successors(Some(Category::Seed), |category| category.next()).collect::<Vec<_>>();
+
+    let max = almanac
+        .conversions
+        .values()
+        .flatten()
+        .flat_map(|mapping| {
+            [
+                mapping.source_range + mapping.len,
+                mapping.destination_range + mapping.len,
+            ]
+        })
+        .chain(almanac.seeds.iter().copied())
+        .chain(
+            almanac
+                .seeds
+                .as_chunks::<2>()
+                .0
+                .iter()
+                .map(|&[start, len]| start + len),
+        )
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let x = |number: u64| SVG_LABEL_WIDTH + number as f64 / max as f64 * SVG_BAR_WIDTH;
+    let top = |row: usize| row as f64 * (SVG_BAR_HEIGHT + SVG_GAP) + SVG_GAP / 2.0;
+
+    let mut svg = Svg::new(
+        SVG_LABEL_WIDTH + SVG_BAR_WIDTH + 10.0,
+        top(categories.len()) - SVG_GAP / 2.0,
+    );
+    for (row, category) in categories.iter().enumerate() {
+        svg.text(
+            (SVG_LABEL_WIDTH - 8.0, top(row) + SVG_BAR_HEIGHT * 0.7),
+            &category.to_string(),
+            &Style::new().font_size(12.0).end_aligned(),
+        )
+        .rect(
+            x(0),
+            top(row),
+            SVG_BAR_WIDTH,
+            SVG_BAR_HEIGHT,
+            &Style::new().fill(color(Highlight::Dim)).opacity(0.3),
+        );
+
+        let mappings = almanac.conversions.get(category).into_iter().flatten();
+        for (idx, mapping) in mappings.enumerate() {
+            let fill = SVG_PALETTE[idx % SVG_PALETTE.len()];
+            let source = (
+                x(mapping.source_range),
+                x(mapping.source_range + mapping.len),
+            );
+            let destination = (
+                x(mapping.destination_range),
+                x(mapping.destination_range + mapping.len),
+            );
+            let (bottom, next_top) = (top(row) + SVG_BAR_HEIGHT, top(row + 1));
+
+            svg.rect(
+                source.0,
+                top(row),
+                source.1 - source.0,
+                SVG_BAR_HEIGHT,
+                &Style::new().fill(fill).opacity(0.8),
+            )
+            .polygon(
+                &[
+                    (source.0, bottom),
+                    (source.1, bottom),
+                    (destination.1, next_top),
+                    (destination.0, next_top),
+                ],
+                &Style::new().fill(fill).opacity(0.25),
+            );
+        }
+    }
+
+    let focus = Style::new().stroke(color(Highlight::Focus), 1.5);
+    match puzzle_part {
+        PuzzlePart::One => {
+            for &seed in &almanac.seeds {
+                let numbers =
+                    iter::successors(Some((Category::Seed, seed)), |&(category, number)| {
+                        almanac.convert(category, number)
+                    });
+                let points = numbers
+                    .enumerate()
+                    .flat_map(|(row, (_, number))| {
+                        [
+                            (x(number), top(row)),
+                            (x(number), top(row) + SVG_BAR_HEIGHT),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                svg.polyline(&points, &focus);
+            }
+        }
+        PuzzlePart::Two => {
+            for &[start, len] in almanac.seeds.as_chunks::<2>().0 {
+                let ranges = iter::successors(
+                    Some((
+                        Category::Seed,
+                        iter::once(start..start + len).collect::<Vec<_>>(),
+                    )),
+                    |(category, ranges)| almanac.convert_ranges(*category, ranges.clone()),
+                );
+                for (row, (_, ranges)) in ranges.enumerate() {
+                    for range in ranges {
+                        svg.rect(
+                            x(range.start),
+                            top(row) + SVG_BAR_HEIGHT / 4.0,
+                            (x(range.end) - x(range.start)).max(1.0),
+                            SVG_BAR_HEIGHT / 2.0,
+                            &Style::new().fill(color(Highlight::Focus)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(svg)
+}
+
+/// A single seed or mapping of an almanac, the parts removed when minimizing an input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AlmanacPart {
+    Seed(u64),
+    Mapping(Category, Mapping),
+}
+
+impl InputPart for AlmanacPart {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        let almanac = Almanac::from_str(input)?;
+
+        let seeds = almanac.seeds.iter().copied().map(AlmanacPart::Seed);
+        let mappings = iter::successors(Some(Category::Seed), |category| category.next()).flat_map(
+            |category| {
+                almanac
+                    .conversions
+                    .get(&category)
+                    .into_iter()
+                    .flatten()
+                    .map(move |mapping| AlmanacPart::Mapping(category, *mapping))
+            },
+        );
+
+        Ok(seeds.chain(mappings).collect())
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        let mut almanac = Almanac {
+            seeds: vec![],
+            conversions: HashMap::new(),
+        };
+        for part in parts {
+            match *part {
+                AlmanacPart::Seed(seed) => almanac.seeds.push(seed),
+                AlmanacPart::Mapping(category, mapping) => almanac
+                    .conversions
+                    .entry(category)
+                    .or_default()
+                    .push(mapping),
+            }
+        }
+
+        almanac.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Category::{Seed, Soil};
+    use aoc_2023_common::assert_snapshot;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(res, 35);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(res, 46);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_almanac_parse() -> eyre::Result<()> {
+        let res = Almanac::from_str(
+            "\
+seeds: 1 2
+
+seed-to-soil map:
+0 0 1
+
+soil-to-fertilizer map:
+1 3 2
+        ",
+        )?;
+        assert_eq!(
+            res,
+            Almanac {
+                seeds: vec![1, 2],
+                conversions: HashMap::from([
+                    (
+                        Seed,
+                        Vec::from([Mapping {
+                            source_range: 0,
+                            destination_range: 0,
+                            len: 1,
+                        }])
+                    ),
+                    (
+                        Soil,
+                        Vec::from([Mapping {
+                            source_range: 3,
+                            destination_range: 1,
+                            len: 2,
+                        }])
+                    )
+                ])
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_svg() -> eyre::Result<()> {
+        let svg = render_svg(EXAMPLE, PuzzlePart::One)?.to_string();
+        // one band per mapping and one path per seed
+        assert_eq!(svg.matches("<polygon").count(), 18);
+        assert_eq!(svg.matches("<polyline").count(), 4);
+        assert!(svg.contains(">humidity</text>"));
+
+        let svg = render_svg(EXAMPLE, PuzzlePart::Two)?.to_string();
+        // every seed range is drawn in all 8 categories, split up further along the way
+        assert!(svg.matches(r##"fill="#e6a700""##).count() > 2 * 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let steps = explain(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(steps.len(), 4);
+        assert_eq!(
+            steps[0].to_string(),
+            "seed 79 ends up at location 82, the lowest location is 82
+seed 79 -> [soil 81]<  (mapping 52 50 48)>
+soil 81 -> [fertilizer 81]  (not mapped)
+fertilizer 81 -> [water 81]  (not mapped)
+water 81 -> [light 74]<  (mapping 18 25 70)>
+light 74 -> [temperature 78]<  (mapping 68 64 13)>
+temperature 78 -> [humidity 78]  (not mapped)
+humidity 78 -> [location 82]<  (mapping 60 56 37)>"
+        );
+
+        let steps = explain(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(steps.len(), 2);
+        assert!(steps[1].caption.ends_with("the lowest location is 46"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<AlmanacPart>(EXAMPLE, |input| {
+            calculate_result(input, PuzzlePart::One).is_ok_and(|res| res == 35)
+        })?;
+        assert_eq!(
+            res,
+            "\
+seeds: 13
+
+seed-to-soil map:
+50 98 2
+
+soil-to-fertilizer map:
+39 0 15
+
+fertilizer-to-water map:
+0 11 42
+
+water-to-light map:
+18 25 70
+
+light-to-temperature map:
+68 64 13
+
+temperature-to-humidity map:
+1 0 69
+
+humidity-to-location map:
+60 56 37
+"
+        );
+
+        Ok(())
+    }
+
+    fn source_categories() -> Vec<Category> {
+        iter::successors(Some(Category::Seed), |category| category.next())
+            .filter(|category| category.next().is_some())
+            .collect()
+    }
+
+    #[test]
+    fn test_almanac_display() -> eyre::Result<()> {
+        let almanac = Almanac::from_str(EXAMPLE)?;
+
+        assert_eq!(almanac.to_string(), format!("{EXAMPLE}\n"));
+
+        Ok(())
+    }
+
+    fn almanac_strategy() -> impl Strategy<Value = Almanac> {
+        let mapping = (
+            0..=u32::MAX as u64,
+            0..=u32::MAX as u64,
+            1..=u32::MAX as u64,
+        )
+            .prop_map(|(source_range, destination_range, len)| Mapping {
+                source_range,
+                destination_range,
+                len,
+            });
+        let conversions = source_categories()
+            .into_iter()
+            .map(|category| {
+                prop::collection::vec(mapping.clone(), 0..5)
+                    .prop_map(move |mappings| (category, mappings))
+            })
+            .collect::<Vec<_>>();
+
+        (
+            prop::collection::vec(0..=u32::MAX as u64, 1..10),
+            conversions,
+        )
+            .prop_map(|(seeds, conversions)| Almanac {
+                seeds,
+                conversions: conversions
+                    .into_iter()
+                    .filter(|(_, mappings)| !mappings.is_empty())
+                    .collect(),
+            })
+    }
+
+    #[test]
+    fn test_snapshot_seed_paths() -> eyre::Result<()> {
+        let almanac = Almanac::from_str(include_str!("input.txt"))?;
+        let paths = almanac
+            .seeds
+            .iter()
+            .map(|&seed| {
+                let mut path = format!("{} {seed}", Category::Seed);
+                let (mut category, mut number) = (Category::Seed, seed);
+                while let Some((next_category, next_number)) = almanac.convert(category, number) {
+                    path += &format!(" -> {next_category} {next_number}");
+                    (category, number) = (next_category, next_number);
+                }
+                path
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_snapshot!("seed_paths", paths)
+    }
+
+    #[test]
+    fn test_snapshot_location_ranges() -> eyre::Result<()> {
+        let almanac = Almanac::from_str(include_str!("input.txt"))?;
+        let ranges = almanac
+            .seeds
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&[start, len]| {
+                let seed_range = start..start + len;
+                let mut locations = almanac
+                    .convert_seed_range_to_location(seed_range.clone())
+                    .context("the almanac has no locations")?;
+                locations.sort_by_key(|range| range.start);
+                let locations = locations
+                    .iter()
+                    .map(|range| format!("{range:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Ok(format!("seeds {seed_range:?} -> locations {locations}"))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?
+            .join("\n");
+
+        assert_snapshot!("location_ranges", ranges)
+    }
+
+    proptest! {
+        #[test]
+        fn test_almanac_parse_roundtrip(almanac in almanac_strategy()) {
+            let rendered = almanac.to_string();
+            prop_assert_eq!(Almanac::from_str(&rendered).ok(), Some(almanac));
+        }
+
+        #[test]
+        fn test_almanac_parse_never_panics(
+            input in "seeds:[0-9 ]{0,16}\n(\n?[a-z]{0,6}-to-[a-z]{0,6} map:\n([0-9 ]{0,16}\n){0,3}){0,4}|\\PC*"
+        ) {
+            let _ = Almanac::from_str(&input);
+        }
+    }
+
+    #[test]
+    fn test_solutions_agree_on_puzzle_input() {
+        let disagreement =
+            solutions().check::<AlmanacPart>(PuzzlePart::One, include_str!("input.txt"));
+        assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn test_solutions_agree(
+            almanac in almanac_strategy(),
+            seed_ranges in prop::collection::vec((0..=u32::MAX as u64, 0..64u64), 1..4),
+        ) {
+            // keep the seed ranges short enough for converting every seed on its own
+            let almanac = Almanac {
+                seeds: seed_ranges.into_iter().flat_map(|(start, len)| [start, len]).collect(),
+                ..almanac
+            };
+            let input = almanac.to_string();
+            for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+                let disagreement = solutions().check::<AlmanacPart>(puzzle_part, &input);
+                prop_assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use aoc_2023_common::differential::Solutions;
+use aoc_2023_common::explain::Highlight;
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::svg::{color, Style, Svg};
+use aoc_2023_common::PuzzlePart;
+
+use std::str::FromStr;
+
+use eyre::{ContextCompat, WrapErr};
+use tracing::{debug_span, instrument};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
+}
+
+impl Race {
+    #[instrument(level = "trace")]
+    pub fn winning_possibilities(&self) -> usize {
+        (1..self.time)
+            .map(|time_pressed| self.calculate_distance(time_pressed))
+            .filter(|distance| distance > &self.record)
+            .count()
+    }
+
+    /// Same as [`Race::winning_possibilities`], but solves `x * (time - x) > record` for the
+    /// shortest winning press time instead of trying every press time.
+    #[instrument(level = "trace")]
+    pub fn winning_possibilities_closed_form(&self) -> usize {
+        let (time, record) = (self.time as u128, self.record as u128);
+        let Some(discriminant) = (time * time).checked_sub(4 * record) else {
+            return 0;
+        };
+
+        // the integer square root only approximates the root, so step to the exact press time
+        let beats_record = |time_pressed: u64| self.calculate_distance(time_pressed) > self.record;
+        let mut shortest = ((time - discriminant.isqrt()) / 2) as u64;
+        while shortest > 1 && beats_record(shortest - 1) {
+            shortest -= 1;
+        }
+        while shortest <= self.time / 2 && !beats_record(shortest) {
+            shortest += 1;
+        }
+
+        if shortest > self.time / 2 {
+            return 0;
+        }
+
+        // distances are symmetric around half the race time
+        (self.time - 2 * shortest + 1) as usize
+    }
+
+    pub fn calculate_distance(&self, time_pressed: u64) -> u64 {
+        if self.time <= time_pressed {
+            return 0;
+        }
+
+        let speed = time_pressed;
+        let travel_time = self.time - time_pressed;
+
+        speed * travel_time
+    }
+}
+
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<usize> {
+    calculate_result_with(input, puzzle_part, Race::winning_possibilities_closed_form)
+}
+
+pub fn solutions() -> Solutions<usize> {
+    Solutions::new()
+        .register(PuzzlePart::One, "closed form", |input| {
+            calculate_result(input, PuzzlePart::One)
+        })
+        .register(PuzzlePart::One, "linear scan", |input| {
+            calculate_result_with(input, PuzzlePart::One, Race::winning_possibilities)
+        })
+        .register(PuzzlePart::Two, "closed form", |input| {
+            calculate_result(input, PuzzlePart::Two)
+        })
+        .register(PuzzlePart::Two, "linear scan", |input| {
+            calculate_result_with(input, PuzzlePart::Two, Race::winning_possibilities)
+        })
+}
+
+#[instrument(skip(input, winning_possibilities))]
+fn calculate_result_with(
+    input: &str,
+    puzzle_part: PuzzlePart,
+    winning_possibilities: fn(&Race) -> usize,
+) -> eyre::Result<usize> {
+    let input = normalize(input);
+    let res = match puzzle_part {
+        PuzzlePart::One => {
+            let races = parse_input_part_1(&input)?;
+            let _solve = debug_span!("solve").entered();
+
+            races
+                .iter()
+                .map(winning_possibilities)
+                .reduce(|acc, x| acc * x)
+                .unwrap_or(0)
+        }
+        PuzzlePart::Two => {
+            let race = parse_input_part_2(&input)?;
+            let _solve = debug_span!("solve").entered();
+            winning_possibilities(&race)
+        }
+    };
+
+    Ok(res)
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input_part_1(input: &str) -> eyre::Result<Vec<Race>> {
+    let mut lines = input.lines();
+    let time_line = lines.next().wrap_err("time line does not exist")?;
+    let record_line = lines.next().wrap_err("record line does not exist")?;
+
+    fn line_iter(line: &str) -> impl Iterator<Item = eyre::Result<u64>> + '_ {
+        line.split(' ')
+            .skip(1)
+            .filter(|s| !s.is_empty())
+            .map(u64::from_str)
+            .map(|res| res.wrap_err("could not parse part to u64"))
+    }
+
+    line_iter(time_line)
+        .zip(line_iter(record_line))
+        .map(|(time, record)| {
+            Ok(Race {
+                time: time?,
+                record: record?,
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input_part_2(input: &str) -> eyre::Result<Race> {
+    let mut lines = input.lines();
+    let time_line = lines.next().wrap_err("time line does not exist")?;
+    let record_line = lines.next().wrap_err("record line does not exist")?;
+
+    let parse_line = |line: &str| {
+        line.splitn(2, ' ')
+            .skip(1)
+            .filter(|s| !s.is_empty())
+            .flat_map(|str| str.chars())
+            .filter_map(|c| c.to_digit(10))
+            .fold(0u64, |acc, x| acc * 10 + x as u64)
+    };
+
+    let time = parse_line(time_line);
+    let record = parse_line(record_line);
+
+    Ok(Race { time, record })
+}
+
+const SVG_PLOT_WIDTH: f64 = 480.0;
+const SVG_PLOT_HEIGHT: f64 = 200.0;
+const SVG_MARGIN: f64 = 30.0;
+/// Points the distance curve is sampled at.
+const SVG_SAMPLES: u64 = 200;
+
+/// Plots the distance over the press time for every race, with the record and the winning press
+/// times highlighted.
+pub fn render_svg(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Svg> {
+    let input = normalize(input);
+    let races = match puzzle_part {
+        PuzzlePart::One => parse_input_part_1(&input)?,
+        PuzzlePart::Two => vec![parse_input_part_2(&input)?],
+    };
+
+    let panel_height = SVG_PLOT_HEIGHT + 2.0 * SVG_MARGIN;
+    let mut svg = Svg::new(
+        SVG_PLOT_WIDTH + 2.0 * SVG_MARGIN,
+        races.len() as f64 * panel_height,
+    );
+    for (idx, race) in races.iter().enumerate() {
+        let top = idx as f64 * panel_height + SVG_MARGIN;
+        let max_distance = race
+            .calculate_distance(race.time / 2)
+            .max(race.record)
+            .max(1);
+        let x = |time_pressed: u64| {
+            SVG_MARGIN + time_pressed as f64 / race.time.max(1) as f64 * SVG_PLOT_WIDTH
+        };
+        let y = |distance: u64| {
+            top + SVG_PLOT_HEIGHT - distance as f64 / max_distance as f64 * SVG_PLOT_HEIGHT
+        };
+
+        let ways = race.winning_possibilities_closed_form() as u64;
+        if ways > 0 {
+            // the winning press times are centered around half the race time
+            let shortest = (race.time + 1 - ways) / 2;
+            svg.rect(
+                x(shortest),
+                top,
+                x(shortest + ways - 1) - x(shortest),
+                SVG_PLOT_HEIGHT,
+                &Style::new().fill(color(Highlight::Match)).opacity(0.2),
+            );
+        }
+
+        let points = (0..=SVG_SAMPLES)
+            .map(|sample| race.time * sample / SVG_SAMPLES)
+            .map(|time_pressed| (x(time_pressed), y(race.calculate_distance(time_pressed))))
+            .collect::<Vec<_>>();
+        svg.line(
+            (x(0), y(0)),
+            (x(race.time), y(0)),
+            &Style::new().stroke(color(Highlight::None), 1.0),
+        )
+        .line(
+            (x(0), y(race.record)),
+            (x(race.time), y(race.record)),
+            &Style::new().stroke(color(Highlight::Dim), 1.0),
+        )
+        .polyline(&points, &Style::new().stroke(color(Highlight::Focus), 2.0))
+        .text(
+            (SVG_MARGIN, top - 8.0),
+            &format!(
+                "{} ms race, record {} mm: {ways} ways to win",
+                race.time, race.record
+            ),
+            &Style::new().font_size(12.0),
+        );
+    }
+
+    Ok(svg)
+}
+
+impl InputPart for Race {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        parse_input_part_1(input)
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        let (mut time_line, mut record_line) = (format!("{:<9}", "Time:"), "Distance:".to_string());
+        for race in parts {
+            let width = race
+                .time
+                .to_string()
+                .len()
+                .max(race.record.to_string().len());
+            time_line += &format!("  {:>width$}", race.time);
+            record_line += &format!("  {:>width$}", race.record);
+        }
+
+        format!("{time_line}\n{record_line}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::One)?;
+        assert_eq!(res, 288);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE, PuzzlePart::Two)?;
+        assert_eq!(res, 71503);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_race_parse_part_1() -> eyre::Result<()> {
+        let res = parse_input_part_1(EXAMPLE)?;
+        assert_eq!(
+            res,
+            Vec::from([
+                Race { time: 7, record: 9 },
+                Race {
+                    time: 15,
+                    record: 40
+                },
+                Race {
+                    time: 30,
+                    record: 200
+                },
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_race_parse_part_2() -> eyre::Result<()> {
+        let res = parse_input_part_2(EXAMPLE)?;
+        assert_eq!(
+            res,
+            Race {
+                time: 71530,
+                record: 940200,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_svg() -> eyre::Result<()> {
+        let svg = render_svg(EXAMPLE, PuzzlePart::One)?.to_string();
+        assert_eq!(svg.matches("<polyline").count(), 3);
+        assert!(svg.contains(">7 ms race, record 9 mm: 4 ways to win</text>"));
+
+        let svg = render_svg(EXAMPLE, PuzzlePart::Two)?.to_string();
+        assert!(svg.contains(">71530 ms race, record 940200 mm: 71503 ways to win</text>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<Race>(EXAMPLE, |input| {
+            parse_input_part_1(input)
+                .is_ok_and(|races| races.iter().any(|race| race.winning_possibilities() >= 9))
+        })?;
+        assert_eq!(res, "Time:       30\nDistance:  200");
+
+        Ok(())
+    }
+
+    fn race_strategy() -> impl Strategy<Value = Race> {
+        (0..2_000u64)
+            .prop_flat_map(|time| (Just(time), 0..=time * time / 4 + 1))
+            .prop_map(|(time, record)| Race { time, record })
+    }
+
+    #[test]
+    fn test_solutions_agree_on_puzzle_input() {
+        let input = include_str!("input.txt");
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            let disagreement = solutions().check::<Race>(puzzle_part, input);
+            assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_solutions_agree_part_1(races in prop::collection::vec(race_strategy(), 1..4)) {
+            let input = Race::render_parts(&races);
+            let disagreement = solutions().check::<Race>(PuzzlePart::One, &input);
+            prop_assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+        }
+
+        // part two concatenates all races, so a single one keeps the linear scan fast
+        #[test]
+        fn test_solutions_agree_part_2(race in race_strategy()) {
+            let input = Race::render_parts(&[race]);
+            let disagreement = solutions().check::<Race>(PuzzlePart::Two, &input);
+            prop_assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+        }
+    }
+}
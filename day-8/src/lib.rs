@@ -0,0 +1,891 @@
+use aoc_2023_common::cycle::{brent, crt, Cycle};
+use aoc_2023_common::explain::{Highlight, Span, Step};
+use aoc_2023_common::graph::{Graph, NodeId};
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::svg::{color, Style, Svg};
+use aoc_2023_common::PuzzlePart;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use eyre::{bail, ContextCompat, WrapErr};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::{debug_span, instrument, trace_span};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    Left,
+    Right,
+}
+
+pub type ElementId = [char; 3];
+
+const START: ElementId = ['A', 'A', 'A'];
+const END: ElementId = ['Z', 'Z', 'Z'];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Element {
+    pub id: ElementId,
+    pub left: ElementId,
+    pub right: ElementId,
+}
+
+/// The elements as a graph with a `Left` and a `Right` edge going out of every element.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Network {
+    graph: Graph<ElementId, Instruction>,
+}
+
+/// How a ghost's walk through the network repeats.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GhostWalk {
+    /// The walk is at the same element and instruction again every `cycle.len` steps from
+    /// `cycle.start` steps on.
+    pub cycle: Cycle,
+    /// The steps after which the walk is at a target, up to the end of its first cycle.
+    pub arrivals: Vec<usize>,
+}
+
+impl TryFrom<char> for Instruction {
+    type Error = eyre::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            _ => bail!("unknown instruction: {c}"),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s
+            .lines()
+            .map(Element::from_str)
+            .map(|res| res.wrap_err("could not parse element"))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self::from_elements(elements))
+    }
+}
+
+impl FromStr for Element {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static ELEMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?<id>[A-Z0-9]{3}) = \((?<left>[A-Z0-9]{3}), (?<right>[A-Z0-9]{3})\)")
+                .unwrap()
+        });
+
+        let captures = ELEMENT_REGEX
+            .captures(s)
+            .wrap_err("invalid element format")?;
+        let parse_element_id = |group_name: &str| -> eyre::Result<ElementId> {
+            captures
+                .name(group_name)
+                .wrap_err("group name not found")?
+                .as_str()
+                .chars()
+                .collect::<Vec<char>>()
+                .as_slice()
+                .try_into()
+                .wrap_err("element ID too long")
+        };
+
+        let id = parse_element_id("id")?;
+        let left = parse_element_id("left")?;
+        let right = parse_element_id("right")?;
+
+        Ok(Self { id, left, right })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "L"),
+            Self::Right => write!(f, "R"),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, element) in self.elements().iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{element}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [i1, i2, i3] = self.id;
+        let [l1, l2, l3] = self.left;
+        let [r1, r2, r3] = self.right;
+
+        write!(f, "{i1}{i2}{i3} = ({l1}{l2}{l3}, {r1}{r2}{r3})")
+    }
+}
+
+impl Network {
+    /// Builds the network from its elements, a later element replacing an earlier one with the
+    /// same id.
+    pub fn from_elements(elements: impl IntoIterator<Item = Element>) -> Self {
+        // adding the elements sorted makes networks with the same elements equal
+        let elements = elements
+            .into_iter()
+            .map(|element| (element.id, element))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut graph = Graph::new();
+        for element in elements.values() {
+            graph.add_edge(element.id, element.left, Instruction::Left);
+            graph.add_edge(element.id, element.right, Instruction::Right);
+        }
+
+        Self { graph }
+    }
+
+    /// The element with the id, if the network defines it rather than only referring to it.
+    pub fn element(&self, id: ElementId) -> Option<Element> {
+        let node = self.graph.id(&id)?;
+        let next = |instruction| Some(*self.graph.key(self.next(node, instruction)?));
+
+        Some(Element {
+            id,
+            left: next(Instruction::Left)?,
+            right: next(Instruction::Right)?,
+        })
+    }
+
+    /// All elements, sorted by id.
+    pub fn elements(&self) -> Vec<Element> {
+        self.graph
+            .nodes()
+            .filter_map(|(_, &id)| self.element(id))
+            .sorted_unstable_by_key(|element| element.id)
+            .collect()
+    }
+
+    /// The elements ghosts start at (sorted by id) and the elements they want to reach.
+    pub fn ghost_positions(&self) -> (Vec<ElementId>, HashSet<ElementId>) {
+        let ids = self.elements().into_iter().map(|element| element.id);
+        let start_positions = ids.clone().filter(|id| id.ends_with(&['A'])).collect();
+        let end_positions = ids.filter(|id| id.ends_with(&['Z'])).collect();
+
+        (start_positions, end_positions)
+    }
+
+    pub fn take_step(&self, cur: ElementId, instruction: Instruction) -> Option<ElementId> {
+        let next = self.next(self.graph.id(&cur)?, instruction)?;
+        Some(*self.graph.key(next))
+    }
+
+    fn next(&self, node: NodeId, instruction: Instruction) -> Option<NodeId> {
+        self.graph
+            .edges(node)
+            .iter()
+            .find(|&&(_, edge)| edge == instruction)
+            .map(|&(next, _)| next)
+    }
+
+    #[instrument(level = "debug", skip(self, target, instructions))]
+    pub fn steps(
+        &self,
+        start: ElementId,
+        target: &HashSet<ElementId>,
+        instructions: &[Instruction],
+    ) -> usize {
+        if instructions.is_empty() {
+            return 0;
+        }
+
+        let (mut steps, mut cur) = (0usize, start);
+        // one batch is one pass over all instructions
+        loop {
+            let _batch = trace_span!("batch", steps, ?cur).entered();
+            for &instruction in instructions {
+                steps += 1;
+                cur = match self.take_step(cur, instruction) {
+                    Some(next) => next,
+                    None => panic!("Unknown node: {cur:?}"),
+                };
+
+                if target.contains(&cur) {
+                    return steps;
+                }
+            }
+        }
+    }
+
+    /// Finds the cycle the walk from `start` ends up in and when it's at a target up to then.
+    #[instrument(level = "debug", skip(self, targets, instructions))]
+    pub fn ghost_walk(
+        &self,
+        start: ElementId,
+        targets: &HashSet<ElementId>,
+        instructions: &[Instruction],
+    ) -> eyre::Result<GhostWalk> {
+        let name = |id: ElementId| id.iter().collect::<String>();
+        let start_node = self
+            .graph
+            .id(&start)
+            .wrap_err_with(|| format!("unknown node: {}", name(start)))?;
+
+        // the state is the current element and the index of the next instruction, `None` once the
+        // walk runs into an element the network doesn't define
+        let step = |state: &Option<(NodeId, usize)>| {
+            let (node, idx) = (*state)?;
+            let next = self.next(node, instructions[idx])?;
+            Some((next, (idx + 1) % instructions.len()))
+        };
+        let cycle = brent(Some((start_node, 0)), step);
+
+        let mut arrivals = vec![];
+        let mut state = Some((start_node, 0));
+        for steps in 1..=cycle.start + cycle.len {
+            let Some((node, _)) = step(&state) else {
+                let (node, _) = state.expect("the walk only stops at an element");
+                bail!("unknown node: {}", name(*self.graph.key(node)));
+            };
+            if targets.contains(self.graph.key(node)) {
+                arrivals.push(steps);
+            }
+            state = step(&state);
+        }
+
+        Ok(GhostWalk { cycle, arrivals })
+    }
+
+    /// The number of steps until all ghosts are at a target at once, if they ever are. Each walk
+    /// is periodic once it's in its cycle, so the arrivals in the cycles are combined with the
+    /// Chinese remainder theorem.
+    #[instrument(level = "debug", skip(self, targets, instructions))]
+    pub fn ghosts_meet(
+        &self,
+        starts: &[ElementId],
+        targets: &HashSet<ElementId>,
+        instructions: &[Instruction],
+    ) -> eyre::Result<Option<usize>> {
+        if instructions.is_empty() {
+            return Ok(Some(0));
+        }
+
+        let walks = starts
+            .iter()
+            .map(|&start| self.ghost_walk(start, targets, instructions))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        // before all walks are in their cycles, the ghosts can only be checked step by step
+        let tail = walks.iter().map(|walk| walk.cycle.start).max().unwrap_or(0);
+        if let Some(steps) = (1..tail).find(|&steps| walks.iter().all(|walk| walk.arrives(steps))) {
+            return Ok(Some(steps));
+        }
+
+        let first = walks
+            .iter()
+            .map(|walk| {
+                walk.arrivals
+                    .iter()
+                    .filter(|&&steps| steps > walk.cycle.start)
+                    .map(|&steps| (steps as i128, walk.cycle.len as i128))
+                    .collect::<Vec<_>>()
+            })
+            .multi_cartesian_product()
+            .filter_map(crt)
+            .map(|(steps, period)| {
+                // the smallest solution may still be in one of the tails, skip ahead past them
+                let behind = (tail.max(1) as i128 - steps).max(0);
+                steps + (behind + period - 1) / period * period
+            })
+            .min();
+
+        Ok(first.map(|steps| steps as usize))
+    }
+}
+
+impl GhostWalk {
+    /// Whether the walk is at a target after `steps` steps.
+    pub fn arrives(&self, steps: usize) -> bool {
+        let steps = if steps > self.cycle.start + self.cycle.len {
+            self.cycle.start + 1 + (steps - self.cycle.start - 1) % self.cycle.len
+        } else {
+            steps
+        };
+
+        self.arrivals.contains(&steps)
+    }
+}
+
+#[instrument(skip(input))]
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<usize> {
+    let (instructions, network) = parse_input(&normalize(input))?;
+    let _solve = debug_span!("solve").entered();
+
+    let res = match puzzle_part {
+        PuzzlePart::One => network.steps(START, &HashSet::from([END]), &instructions),
+        PuzzlePart::Two => {
+            let (start_positions, end_positions) = network.ghost_positions();
+
+            network
+                .ghosts_meet(&start_positions, &end_positions, &instructions)?
+                .wrap_err("the ghosts are never all at a Z node at once")?
+        }
+    };
+
+    Ok(res)
+}
+
+/// Steps shown one by one before the rest of a walk is only summed up.
+const MAX_EXPLAINED_STEPS: usize = 1000;
+/// Instructions shown before and after the current one.
+const INSTRUCTION_CONTEXT: usize = 20;
+
+/// Walks through the network step by step: from `AAA` for puzzle part one, with all ghosts at once
+/// for puzzle part two. Long walks are cut short and summed up at the end.
+pub fn explain(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Vec<Step>> {
+    let (instructions, network) = parse_input(&normalize(input))?;
+    if instructions.is_empty() {
+        bail!("no instructions to follow");
+    }
+
+    let (mut positions, targets) = match puzzle_part {
+        PuzzlePart::One => (vec![START], HashSet::from([END])),
+        PuzzlePart::Two => network.ghost_positions(),
+    };
+    let name = |id: ElementId| id.iter().collect::<String>();
+
+    let mut steps = vec![];
+    for (step, (idx, &instruction)) in instructions
+        .iter()
+        .enumerate()
+        .cycle()
+        .take(MAX_EXPLAINED_STEPS)
+        .enumerate()
+    {
+        let window = idx.saturating_sub(INSTRUCTION_CONTEXT)
+            ..(idx + INSTRUCTION_CONTEXT + 1).min(instructions.len());
+        let instruction_line = window
+            .map(|i| {
+                let highlight = if i == idx {
+                    Highlight::Focus
+                } else {
+                    Highlight::Dim
+                };
+                Span::highlighted(instructions[i].to_string(), highlight)
+            })
+            .collect();
+        let mut explained = Step::new(format!(
+            "step {}: instruction {} of {} is {instruction}",
+            step + 1,
+            idx + 1,
+            instructions.len()
+        ))
+        .line(instruction_line);
+
+        for position in positions.iter_mut() {
+            let element = network
+                .element(*position)
+                .wrap_err_with(|| format!("unknown node: {}", name(*position)))?;
+            let (left, right) = match instruction {
+                Instruction::Left => (Highlight::Focus, Highlight::Dim),
+                Instruction::Right => (Highlight::Dim, Highlight::Focus),
+            };
+            *position = network
+                .take_step(*position, instruction)
+                .expect("element exists");
+
+            let mut line = vec![
+                Span::plain(format!("{} = (", name(element.id))),
+                Span::highlighted(name(element.left), left),
+                Span::plain(", "),
+                Span::highlighted(name(element.right), right),
+                Span::plain(")"),
+            ];
+            if targets.contains(position) {
+                line.push(Span::highlighted(
+                    format!("  reached {}", name(*position)),
+                    Highlight::Match,
+                ));
+            }
+            explained = explained.line(line);
+        }
+        steps.push(explained);
+
+        if positions.iter().all(|position| targets.contains(position)) {
+            return Ok(steps);
+        }
+    }
+
+    let summary = match puzzle_part {
+        PuzzlePart::One => Step::new(format!(
+            "... and so on, until ZZZ is reached after {} steps",
+            network.steps(START, &targets, &instructions)
+        )),
+        PuzzlePart::Two => {
+            let (start_positions, _) = network.ghost_positions();
+            let total = network
+                .ghosts_meet(&start_positions, &targets, &instructions)?
+                .wrap_err("the ghosts are never all at a Z node at once")?;
+
+            start_positions.iter().try_fold(
+                Step::new(format!(
+                    "... and so on, until all ghosts are at a Z node at once after {total} steps, \
+                     found by lining up the cycles of their walks"
+                )),
+                |step, &start| -> eyre::Result<Step> {
+                    let walk = network.ghost_walk(start, &targets, &instructions)?;
+                    let first = walk.arrivals.first().copied().unwrap_or_default();
+
+                    Ok(step.line(vec![
+                        Span::plain(format!(
+                            "the ghost starting at {} first reaches a Z node after ",
+                            name(start)
+                        )),
+                        Span::highlighted(first.to_string(), Highlight::Match),
+                        Span::plain(" steps, its walk repeats every "),
+                        Span::highlighted(walk.cycle.len.to_string(), Highlight::Focus),
+                        Span::plain(" steps"),
+                    ]))
+                },
+            )?
+        }
+    };
+    steps.push(summary);
+
+    Ok(steps)
+}
+
+/// Radius of the circle the elements are laid out on.
+const SVG_RADIUS: f64 = 400.0;
+const SVG_MARGIN: f64 = 40.0;
+/// Networks up to this size get their elements labeled.
+const SVG_MAX_LABELED: usize = 64;
+
+/// Draws the network with the elements on a circle, the start and end elements highlighted and
+/// the edges the walk (all ghosts' walks for puzzle part two) takes on the way emphasized.
+pub fn render_svg(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Svg> {
+    let (instructions, network) = parse_input(&normalize(input))?;
+    let (starts, targets) = match puzzle_part {
+        PuzzlePart::One => (vec![START], HashSet::from([END])),
+        PuzzlePart::Two => network.ghost_positions(),
+    };
+
+    let elements = network.elements();
+    let ids = elements
+        .iter()
+        .map(|element| element.id)
+        .collect::<Vec<_>>();
+    let center = SVG_MARGIN + SVG_RADIUS;
+    let positions = ids
+        .iter()
+        .enumerate()
+        .map(|(idx, &id)| {
+            let angle = idx as f64 / ids.len() as f64 * std::f64::consts::TAU;
+            (
+                id,
+                (
+                    center + SVG_RADIUS * angle.cos(),
+                    center + SVG_RADIUS * angle.sin(),
+                ),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    // a walk repeats once it's at the same element at the same instruction again
+    let max_steps = instructions.len() * ids.len();
+    let mut walked = HashSet::new();
+    for &start in &starts {
+        let mut cur = start;
+        for &instruction in instructions.iter().cycle().take(max_steps) {
+            let Some(next) = network.take_step(cur, instruction) else {
+                break;
+            };
+            walked.insert((cur, next));
+            cur = next;
+            if targets.contains(&cur) {
+                break;
+            }
+        }
+    }
+
+    let mut svg = Svg::new(2.0 * center, 2.0 * center);
+    let dim = Style::new().stroke(color(Highlight::Dim), 0.5).opacity(0.6);
+    for element in &elements {
+        for next in [element.left, element.right] {
+            if let (Some(&from), Some(&to)) = (positions.get(&element.id), positions.get(&next)) {
+                svg.line(from, to, &dim);
+            }
+        }
+    }
+    let focus = Style::new().stroke(color(Highlight::Focus), 1.5);
+    for (from, to) in &walked {
+        if let (Some(&from), Some(&to)) = (positions.get(from), positions.get(to)) {
+            svg.line(from, to, &focus);
+        }
+    }
+
+    let name = |id: &ElementId| id.iter().collect::<String>();
+    for id in &ids {
+        let highlight = if starts.contains(id) {
+            Highlight::Focus
+        } else if targets.contains(id) {
+            Highlight::Match
+        } else {
+            Highlight::None
+        };
+        let radius = if highlight == Highlight::None {
+            2.0
+        } else {
+            5.0
+        };
+        let (x, y) = positions[id];
+        svg.circle((x, y), radius, &Style::new().fill(color(highlight)));
+        if ids.len() <= SVG_MAX_LABELED || highlight != Highlight::None {
+            svg.text(
+                (x, y - 8.0),
+                &name(id),
+                &Style::new().font_size(10.0).centered(),
+            );
+        }
+    }
+
+    Ok(svg)
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input(input: &str) -> eyre::Result<(Vec<Instruction>, Network)> {
+    let (instructions_line, networks) = input.split_once("\n\n").wrap_err("invalid format")?;
+
+    let instructions = instructions_line
+        .chars()
+        .map(Instruction::try_from)
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    let network = Network::from_str(networks)?;
+
+    Ok((instructions, network))
+}
+
+/// A single instruction or network element, the parts removed when minimizing an input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DocumentPart {
+    Instruction(Instruction),
+    Element(Element),
+}
+
+impl InputPart for DocumentPart {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        let (instructions, network) = parse_input(input)?;
+
+        let instructions = instructions.into_iter().map(DocumentPart::Instruction);
+        let elements = network.elements().into_iter().map(DocumentPart::Element);
+
+        Ok(instructions.chain(elements).collect())
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        let instructions = parts
+            .iter()
+            .filter_map(|part| match part {
+                DocumentPart::Instruction(instruction) => Some(instruction),
+                _ => None,
+            })
+            .join("");
+        let elements = parts
+            .iter()
+            .filter_map(|part| match part {
+                DocumentPart::Element(element) => Some(element),
+                _ => None,
+            })
+            .join("\n");
+
+        format!("{instructions}\n\n{elements}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::minimize::{minimize, panics};
+    use proptest::prelude::*;
+
+    const EXAMPLE_DIRECT_PART_1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+
+    const EXAMPLE_CIRCULAR_PART_1: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_PART_2: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1_direct() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE_DIRECT_PART_1, PuzzlePart::One)?;
+        assert_eq!(res, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1_circular() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE_CIRCULAR_PART_1, PuzzlePart::One)?;
+        assert_eq!(res, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_crlf() -> eyre::Result<()> {
+        let input = format!("\u{feff}{}", EXAMPLE_CIRCULAR_PART_1.replace('\n', "\r\n"));
+        let res = calculate_result(&input, PuzzlePart::One)?;
+        assert_eq!(res, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result(EXAMPLE_PART_2, PuzzlePart::Two)?;
+        assert_eq!(res, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ghosts_meet_with_offset_cycles() -> eyre::Result<()> {
+        // 11Z is reached after 2, 4, 6, ... steps and 22Z after 1, 4, 7, ... steps, so the least
+        // common multiple of the first arrivals would be wrong
+        let (instructions, network) = parse_input(
+            "\
+L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+",
+        )?;
+        let (starts, targets) = network.ghost_positions();
+
+        assert_eq!(
+            network.ghost_walk(starts[0], &targets, &instructions)?,
+            GhostWalk {
+                cycle: Cycle { start: 1, len: 2 },
+                arrivals: vec![2],
+            }
+        );
+        assert_eq!(
+            network.ghost_walk(starts[1], &targets, &instructions)?,
+            GhostWalk {
+                cycle: Cycle { start: 1, len: 3 },
+                arrivals: vec![1, 4],
+            }
+        );
+        assert_eq!(
+            network.ghosts_meet(&starts, &targets, &instructions)?,
+            Some(4)
+        );
+        assert_eq!(calculate_result(EXAMPLE_PART_2, PuzzlePart::Two)?, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ghosts_never_meet() -> eyre::Result<()> {
+        let (instructions, network) = parse_input(
+            "\
+L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)
+",
+        )?;
+        let (starts, targets) = network.ghost_positions();
+
+        assert_eq!(network.ghosts_meet(&starts, &targets, &instructions)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_parse() -> eyre::Result<()> {
+        let (instructions, network) = parse_input(EXAMPLE_CIRCULAR_PART_1)?;
+
+        assert_eq!(
+            instructions,
+            vec![Instruction::Left, Instruction::Left, Instruction::Right]
+        );
+
+        let aaa = ['A', 'A', 'A'];
+        let bbb = ['B', 'B', 'B'];
+        let zzz = ['Z', 'Z', 'Z'];
+
+        assert_eq!(
+            network,
+            Network::from_elements([
+                Element {
+                    id: aaa,
+                    left: bbb,
+                    right: bbb,
+                },
+                Element {
+                    id: bbb,
+                    left: aaa,
+                    right: zzz,
+                },
+                Element {
+                    id: zzz,
+                    left: zzz,
+                    right: zzz,
+                },
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_display() -> eyre::Result<()> {
+        let (instructions, network) = parse_input(EXAMPLE_CIRCULAR_PART_1)?;
+        let instructions = instructions.iter().map(Instruction::to_string).join("");
+
+        assert_eq!(
+            format!("{instructions}\n\n{network}\n"),
+            EXAMPLE_CIRCULAR_PART_1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_svg() -> eyre::Result<()> {
+        let svg = render_svg(EXAMPLE_CIRCULAR_PART_1, PuzzlePart::One)?.to_string();
+        // AAA -> BBB -> AAA -> BBB -> ZZZ walks 3 distinct edges
+        assert_eq!(svg.matches("<line").count(), 6 + 3);
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.contains(">ZZZ</text>"));
+
+        let svg = render_svg(EXAMPLE_PART_2, PuzzlePart::Two)?.to_string();
+        assert_eq!(svg.matches(r##"<circle cx"##).count(), 8);
+        assert_eq!(svg.matches(r##"r="5" fill="#e6a700""##).count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let steps = explain(EXAMPLE_CIRCULAR_PART_1, PuzzlePart::One)?;
+        assert_eq!(steps.len(), 6);
+        assert_eq!(
+            steps[0].to_string(),
+            "step 1: instruction 1 of 3 is L\n[L]LR\nAAA = ([BBB], BBB)"
+        );
+        assert_eq!(
+            steps[5].to_string(),
+            "step 6: instruction 3 of 3 is R\nLL[R]\nBBB = (AAA, [ZZZ])<  reached ZZZ>"
+        );
+
+        let steps = explain(EXAMPLE_PART_2, PuzzlePart::Two)?;
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[5].lines.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<DocumentPart>(
+            "\
+LLR
+
+AAA = (BBB, BBB)
+CCC = (ZZZ, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+",
+            |input| panics(|| calculate_result(input, PuzzlePart::One)),
+        )?;
+        assert_eq!(res, "R\n\nAAA = (BBB, BBB)");
+
+        Ok(())
+    }
+
+    fn element_id_strategy() -> impl Strategy<Value = ElementId> {
+        let c = prop::sample::select(('A'..='Z').chain('0'..='9').collect::<Vec<_>>());
+        prop::array::uniform3(c)
+    }
+
+    fn element_strategy() -> impl Strategy<Value = Element> {
+        (
+            element_id_strategy(),
+            element_id_strategy(),
+            element_id_strategy(),
+        )
+            .prop_map(|(id, left, right)| Element { id, left, right })
+    }
+
+    fn network_strategy() -> impl Strategy<Value = Network> {
+        prop::collection::vec(element_strategy(), 0..16).prop_map(Network::from_elements)
+    }
+
+    proptest! {
+        #[test]
+        fn test_network_parse_roundtrip(network in network_strategy()) {
+            let rendered = network.to_string();
+            prop_assert_eq!(Network::from_str(&rendered).ok(), Some(network));
+        }
+
+        #[test]
+        fn test_element_parse_roundtrip(element in element_strategy()) {
+            let rendered = element.to_string();
+            prop_assert_eq!(Element::from_str(&rendered).ok(), Some(element));
+        }
+
+        #[test]
+        fn test_element_parse_never_panics(input in "[A-Z0-9]{0,4} = \\([A-Z0-9]{0,4}, [A-Z0-9]{0,4}\\)|\\PC*") {
+            let _ = Element::from_str(&input);
+        }
+    }
+}
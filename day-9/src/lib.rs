@@ -0,0 +1,555 @@
+#![feature(iter_map_windows)]
+
+use aoc_2023_common::differential::Solutions;
+use aoc_2023_common::explain::{Highlight, Span, Step};
+use aoc_2023_common::input::try_fold_lines;
+use aoc_2023_common::minimize::InputPart;
+use aoc_2023_common::normalize::normalize;
+use aoc_2023_common::svg::{color, Style, Svg};
+use aoc_2023_common::PuzzlePart;
+
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use eyre::{ContextCompat, WrapErr};
+use itertools::Itertools;
+use tracing::{debug_span, instrument, trace_span};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct History(pub Vec<i64>);
+
+impl FromStr for History {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let history = s
+            .split(' ')
+            .map(i64::from_str)
+            .map(|res| res.wrap_err("couldn't parse history number"))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self(history))
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, value) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl History {
+    pub fn extrapolate_next_value(&self) -> i64 {
+        self.calculate_diff()
+            .iter()
+            .rev()
+            .map(|row| row.last().copied().unwrap_or_default())
+            .sum()
+    }
+
+    pub fn extrapolate_prev_value(&self) -> i64 {
+        self.calculate_diff()
+            .iter()
+            .rev()
+            .map(|row| row.first().copied().unwrap_or_default())
+            .fold(0, |acc, x| x - acc)
+    }
+
+    /// Same as [`History::extrapolate_next_value`], but sums up the difference table in closed
+    /// form: `f(n) = Σ (-1)^(n-1-i) * C(n, i) * f(i)`. `None` if the binomial coefficients of long
+    /// histories or the sum overflow.
+    pub fn extrapolate_next_value_closed_form(&self) -> Option<i64> {
+        let len = self.0.len();
+        let next_value = self
+            .0
+            .iter()
+            .enumerate()
+            .try_fold(0i128, |sum, (idx, &value)| {
+                let term = binomial(len, idx)?.checked_mul(i128::from(value))?;
+                if (len - 1 - idx).is_multiple_of(2) {
+                    sum.checked_add(term)
+                } else {
+                    sum.checked_sub(term)
+                }
+            })?;
+
+        i64::try_from(next_value).ok()
+    }
+
+    /// Same as [`History::extrapolate_prev_value`], but sums up the difference table in closed
+    /// form: `f(-1) = Σ (-1)^i * C(n, i+1) * f(i)`. `None` if the binomial coefficients of long
+    /// histories or the sum overflow.
+    pub fn extrapolate_prev_value_closed_form(&self) -> Option<i64> {
+        let len = self.0.len();
+        let prev_value = self
+            .0
+            .iter()
+            .enumerate()
+            .try_fold(0i128, |sum, (idx, &value)| {
+                let term = binomial(len, idx + 1)?.checked_mul(i128::from(value))?;
+                if idx.is_multiple_of(2) {
+                    sum.checked_add(term)
+                } else {
+                    sum.checked_sub(term)
+                }
+            })?;
+
+        i64::try_from(prev_value).ok()
+    }
+
+    #[instrument(level = "trace", skip(self), fields(len = self.0.len()))]
+    fn calculate_diff(&self) -> Vec<Vec<i64>> {
+        let mut row = self.0.clone();
+        let mut rows = vec![row.clone()];
+
+        while row.iter().any(|&x| x != 0) {
+            let _row = trace_span!("row", depth = rows.len()).entered();
+            row = row.iter().copied().map_windows(|[a, b]| *b - *a).collect();
+
+            rows.push(row.clone());
+        }
+
+        rows
+    }
+}
+
+/// `C(n, k)`, or `None` if it doesn't fit into an `i128`.
+fn binomial(n: usize, k: usize) -> Option<i128> {
+    (0..k).try_fold(1i128, |acc, i| {
+        Some(acc.checked_mul((n - i) as i128)? / (i + 1) as i128)
+    })
+}
+
+pub fn calculate_result(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<i64> {
+    calculate_result_from_reader(normalize(input).as_bytes(), puzzle_part)
+}
+
+/// Same as [`calculate_result`], but reads the input history by history.
+#[instrument(name = "calculate_result", skip(reader))]
+pub fn calculate_result_from_reader(
+    reader: impl BufRead,
+    puzzle_part: PuzzlePart,
+) -> eyre::Result<i64> {
+    let extrapolate = match puzzle_part {
+        PuzzlePart::One => History::extrapolate_next_value,
+        PuzzlePart::Two => History::extrapolate_prev_value,
+    };
+
+    try_fold_lines(reader, 0, |sum, line| {
+        Ok(sum + extrapolate(&History::from_str(line)?))
+    })
+}
+
+pub fn solutions() -> Solutions<i64> {
+    Solutions::new()
+        .register(PuzzlePart::One, "closed form", |input| {
+            sum_extrapolated(input, History::extrapolate_next_value_closed_form)
+        })
+        .register(PuzzlePart::One, "difference table", |input| {
+            calculate_result(input, PuzzlePart::One)
+        })
+        .register(PuzzlePart::Two, "closed form", |input| {
+            sum_extrapolated(input, History::extrapolate_prev_value_closed_form)
+        })
+        .register(PuzzlePart::Two, "difference table", |input| {
+            calculate_result(input, PuzzlePart::Two)
+        })
+}
+
+#[instrument(skip_all)]
+fn sum_extrapolated(input: &str, extrapolate: fn(&History) -> Option<i64>) -> eyre::Result<i64> {
+    let histories = parse_input(&normalize(input))?;
+    let _solve = debug_span!("solve").entered();
+
+    histories
+        .iter()
+        .map(extrapolate)
+        .try_fold(0i64, |sum, value| sum.checked_add(value?))
+        .wrap_err("closed form overflows")
+}
+
+/// Shows the difference pyramid of every history, with the extrapolated values highlighted.
+pub fn explain(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Vec<Step>> {
+    let histories = parse_input(&normalize(input))?;
+
+    let mut sum = 0;
+    let steps = histories
+        .iter()
+        .enumerate()
+        .map(|(idx, history)| {
+            let rows = history.calculate_diff();
+            let extrapolated = extrapolate_rows(&rows, puzzle_part);
+            let value = extrapolated[0];
+            sum += value;
+
+            let width = rows
+                .iter()
+                .flatten()
+                .chain(&extrapolated)
+                .map(|value| value.to_string().len())
+                .max()
+                .unwrap_or_default()
+                + 2;
+            let cells = |values: &[i64]| {
+                values
+                    .iter()
+                    .map(|value| format!("{value:>width$}"))
+                    .collect::<String>()
+            };
+
+            let caption = match puzzle_part {
+                PuzzlePart::One => format!("history {}: next value {value}", idx + 1),
+                PuzzlePart::Two => format!("history {}: previous value {value}", idx + 1),
+            };
+            rows.iter().zip(&extrapolated).enumerate().fold(
+                Step::new(format!("{caption}, the sum is now {sum}")),
+                |step, (depth, (row, &extrapolated))| {
+                    let indent = Span::plain(" ".repeat(depth * width / 2));
+                    let extrapolated = Span::highlighted(cells(&[extrapolated]), Highlight::Focus);
+                    step.line(match puzzle_part {
+                        PuzzlePart::One => vec![indent, Span::plain(cells(row)), extrapolated],
+                        PuzzlePart::Two => vec![indent, extrapolated, Span::plain(cells(row))],
+                    })
+                },
+            )
+        })
+        .collect();
+
+    Ok(steps)
+}
+
+/// The extrapolated value of every row of a difference table, computed from the bottom up: the
+/// next values for puzzle part one, the previous ones for puzzle part two.
+fn extrapolate_rows(rows: &[Vec<i64>], puzzle_part: PuzzlePart) -> Vec<i64> {
+    let mut extrapolated = vec![0; rows.len()];
+    for (depth, row) in rows.iter().enumerate().rev() {
+        let below = extrapolated.get(depth + 1).copied().unwrap_or_default();
+        extrapolated[depth] = match puzzle_part {
+            PuzzlePart::One => row.last().copied().unwrap_or_default() + below,
+            PuzzlePart::Two => row.first().copied().unwrap_or_default() - below,
+        };
+    }
+
+    extrapolated
+}
+
+const SVG_CELL_WIDTH: f64 = 64.0;
+const SVG_CELL_HEIGHT: f64 = 20.0;
+/// Space between two pyramids.
+const SVG_GAP: f64 = 16.0;
+
+/// Draws the difference pyramid of every history, with the extrapolated values highlighted.
+pub fn render_svg(input: &str, puzzle_part: PuzzlePart) -> eyre::Result<Svg> {
+    let histories = parse_input(&normalize(input))?;
+    let pyramids = histories
+        .iter()
+        .map(|history| {
+            let rows = history.calculate_diff();
+            let extrapolated = extrapolate_rows(&rows, puzzle_part);
+            (rows, extrapolated)
+        })
+        .collect::<Vec<_>>();
+
+    let columns = histories
+        .iter()
+        .map(|history| history.0.len() + 1)
+        .max()
+        .unwrap_or_default();
+    let height = pyramids
+        .iter()
+        .map(|(rows, _)| rows.len() as f64 * SVG_CELL_HEIGHT + SVG_GAP)
+        .sum::<f64>();
+    let mut svg = Svg::new(columns as f64 * SVG_CELL_WIDTH + SVG_GAP, height + SVG_GAP);
+
+    let mut top = SVG_GAP;
+    for (rows, extrapolated) in &pyramids {
+        for (depth, (row, &extrapolated)) in rows.iter().zip(extrapolated).enumerate() {
+            let y = top + depth as f64 * SVG_CELL_HEIGHT;
+            let cells = match puzzle_part {
+                PuzzlePart::One => row
+                    .iter()
+                    .map(|&value| (value, Highlight::None))
+                    .chain([(extrapolated, Highlight::Focus)])
+                    .collect::<Vec<_>>(),
+                PuzzlePart::Two => [(extrapolated, Highlight::Focus)]
+                    .into_iter()
+                    .chain(row.iter().map(|&value| (value, Highlight::None)))
+                    .collect(),
+            };
+
+            for (column, (value, highlight)) in cells.into_iter().enumerate() {
+                // every row is shifted by half a cell, so each difference sits between its values
+                let x = SVG_GAP / 2.0 + (column as f64 + depth as f64 / 2.0) * SVG_CELL_WIDTH;
+                if highlight == Highlight::Focus {
+                    svg.rect(
+                        x,
+                        y,
+                        SVG_CELL_WIDTH,
+                        SVG_CELL_HEIGHT,
+                        &Style::new().fill(color(highlight)).opacity(0.4),
+                    );
+                }
+                svg.text(
+                    (x + SVG_CELL_WIDTH / 2.0, y + SVG_CELL_HEIGHT * 0.7),
+                    &value.to_string(),
+                    &Style::new()
+                        .font_size(11.0)
+                        .fill(color(if value == 0 {
+                            Highlight::Dim
+                        } else {
+                            Highlight::None
+                        }))
+                        .centered(),
+                );
+            }
+        }
+        top += rows.len() as f64 * SVG_CELL_HEIGHT + SVG_GAP;
+    }
+
+    Ok(svg)
+}
+
+#[instrument(level = "debug", skip_all)]
+fn parse_input(input: &str) -> eyre::Result<Vec<History>> {
+    input.lines().map(History::from_str).collect()
+}
+
+impl InputPart for History {
+    fn parse_parts(input: &str) -> eyre::Result<Vec<Self>> {
+        parse_input(input)
+    }
+
+    fn render_parts(parts: &[Self]) -> String {
+        parts.iter().join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_common::assert_snapshot;
+    use aoc_2023_common::minimize::minimize;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_calculate_result_puzzle_part_1() -> eyre::Result<()> {
+        let res = calculate_result(
+            "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45",
+            PuzzlePart::One,
+        )?;
+        assert_eq!(res, 114);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_puzzle_part_2() -> eyre::Result<()> {
+        let res = calculate_result("10 13 16 21 30 45", PuzzlePart::Two)?;
+        assert_eq!(res, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_result_from_reader() -> eyre::Result<()> {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n";
+        // a tiny buffer makes lines span several reads
+        let reader = BufReader::with_capacity(4, input.as_bytes());
+        assert_eq!(calculate_result_from_reader(reader, PuzzlePart::Two)?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_extrapolate_positive() -> eyre::Result<()> {
+        let history = History::from_str("0 3 6 9 12 15")?;
+
+        assert_eq!(history.extrapolate_next_value(), 18);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_extrapolate_negative() -> eyre::Result<()> {
+        let history = History::from_str(
+            "6 1 -4 -9 -14 -19 -24 -29 -34 -39 -44 -49 -54 -59 -64 -69 -74 -79 -84 -89 -94",
+        )?;
+
+        assert_eq!(history.extrapolate_next_value(), -99);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_history() -> eyre::Result<()> {
+        let history = History((0..200).map(|x| 3 * x - 7).collect());
+        assert_eq!(history.extrapolate_next_value(), 3 * 200 - 7);
+        assert_eq!(history.extrapolate_prev_value(), -10);
+        assert_eq!(history.extrapolate_next_value_closed_form(), None);
+        assert_eq!(history.extrapolate_prev_value_closed_form(), None);
+
+        let input = history.to_string();
+        assert_eq!(calculate_result(&input, PuzzlePart::One)?, 593);
+        assert_eq!(calculate_result(&input, PuzzlePart::Two)?, -10);
+        // the closed form overflows instead of answering
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            assert!(!solutions().agree(puzzle_part, &input));
+        }
+
+        // short enough for the closed form, both have to agree
+        let history = History((0..60).map(|x| x * x - 5 * x + 1).collect());
+        assert_eq!(
+            history.extrapolate_next_value_closed_form(),
+            Some(history.extrapolate_next_value())
+        );
+        assert_eq!(
+            history.extrapolate_prev_value_closed_form(),
+            Some(history.extrapolate_prev_value())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_parse() -> eyre::Result<()> {
+        let history = History::from_str("0 3 6 9 12 15")?;
+
+        assert_eq!(history, History(vec![0, 3, 6, 9, 12, 15]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_display() -> eyre::Result<()> {
+        let history = History::from_str("6 1 -4 -9")?;
+
+        assert_eq!(history.to_string(), "6 1 -4 -9");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_svg() -> eyre::Result<()> {
+        let svg = render_svg("0 3 6 9 12 15\n10 13 16 21 30 45", PuzzlePart::Two)?.to_string();
+
+        // 3 and 5 rows
+        assert_eq!(svg.matches("<rect x=").count(), 3 + 5);
+        assert!(svg.contains(">-3</text>"));
+        assert!(svg.contains(">5</text>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let steps = explain("0 3 6 9 12 15\n1 3 6 10 15 21", PuzzlePart::One)?;
+        assert_eq!(steps.len(), 2);
+        assert_eq!(
+            steps[0].to_string(),
+            "history 1: next value 18, the sum is now 18
+   0   3   6   9  12  15[  18]
+     3   3   3   3   3[   3]
+       0   0   0   0[   0]"
+        );
+        assert_eq!(
+            steps[1].caption,
+            "history 2: next value 28, the sum is now 46"
+        );
+
+        let steps = explain("10 13 16 21 30 45", PuzzlePart::Two)?;
+        assert_eq!(
+            steps[0].lines[0].iter().map(|span| &*span.text).join(""),
+            "   5  10  13  16  21  30  45"
+        );
+        assert_eq!(
+            steps[0].caption,
+            "history 1: previous value 5, the sum is now 5"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> eyre::Result<()> {
+        let res = minimize::<History>(
+            "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45",
+            |input| {
+                parse_input(input).is_ok_and(|histories| {
+                    histories
+                        .iter()
+                        .any(|history| history.extrapolate_prev_value() < 0)
+                })
+            },
+        )?;
+        assert_eq!(res, "0 3 6 9 12 15");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solutions_agree_on_puzzle_input() {
+        let input = include_str!("input.txt");
+        for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+            let disagreement = solutions().check::<History>(puzzle_part, input);
+            assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_extrapolations() -> eyre::Result<()> {
+        let histories = parse_input(include_str!("input.txt"))?;
+        let extrapolations = histories
+            .iter()
+            .map(|history| {
+                format!(
+                    "{history}: previous {}, next {}",
+                    history.extrapolate_prev_value(),
+                    history.extrapolate_next_value()
+                )
+            })
+            .join("\n");
+
+        assert_snapshot!("extrapolations", extrapolations)
+    }
+
+    proptest! {
+        #[test]
+        fn test_history_parse_roundtrip(values in prop::collection::vec(any::<i64>(), 1..32)) {
+            let history = History(values);
+            let rendered = history.to_string();
+            prop_assert_eq!(History::from_str(&rendered).ok(), Some(history));
+        }
+
+        #[test]
+        fn test_history_parse_never_panics(input in "[0-9 -]{0,64}|\\PC*") {
+            let _ = History::from_str(&input);
+        }
+
+        // small values keep the difference table from overflowing
+        #[test]
+        fn test_solutions_agree(
+            histories in prop::collection::vec(prop::collection::vec(-1_000i64..1_000, 1..16), 1..4)
+        ) {
+            let input = History::render_parts(&histories.into_iter().map(History).collect::<Vec<_>>());
+            for puzzle_part in [PuzzlePart::One, PuzzlePart::Two] {
+                let disagreement = solutions().check::<History>(puzzle_part, &input);
+                prop_assert!(disagreement.is_none(), "{}", disagreement.unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    // the checked-in `include/aoc_2023.h` is compared to this one by `test_header_up_to_date`, so
+    // builds never write to the source tree
+    cbindgen::generate(&crate_dir)
+        .expect("couldn't generate C header")
+        .write_to_file(out_dir.join("aoc_2023.h"));
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,52 @@
+#ifndef AOC_2023_H
+#define AOC_2023_H
+
+/* Generated by cbindgen from src/lib.rs, don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of [`aoc_solve`]. New variants are only ever appended, so the values stay stable.
+ */
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  AOC_STATUS_NULL_POINTER = 1,
+  AOC_STATUS_UNKNOWN_DAY = 2,
+  AOC_STATUS_UNKNOWN_PART = 3,
+  AOC_STATUS_INVALID_UTF8 = 4,
+  AOC_STATUS_SOLVE_FAILED = 5,
+  AOC_STATUS_PANICKED = 6,
+} AocStatus;
+
+/**
+ * Solves `part` (1 or 2) of `day` for the `input_len` bytes of UTF-8 at `input`.
+ *
+ * On success the answer is stored in `result`; if the solver fails, the error message is stored
+ * there instead. Either way the string is owned by the caller and has to be released with
+ * [`aoc_free_string`]. `result` is set to null if there is nothing to report.
+ *
+ * # Safety
+ *
+ * `input` must point to `input_len` readable bytes (or may be null if `input_len` is 0) and
+ * `result` must point to writable storage for a pointer.
+ */
+enum AocStatus aoc_solve(uint8_t day,
+                         uint8_t part,
+                         const uint8_t *input,
+                         size_t input_len,
+                         char **result);
+
+/**
+ * Releases a string returned by [`aoc_solve`]. Does nothing for null.
+ *
+ * # Safety
+ *
+ * `s` must be null or a string returned by [`aoc_solve`] that hasn't been released yet.
+ */
+void aoc_free_string(char *s);
+
+#endif /* AOC_2023_H */
@@ -0,0 +1,151 @@
+//! C ABI for the day solvers, see `include/aoc_2023.h` for the header, which is kept in sync with
+//! the one the build script generates by `test_header_up_to_date`.
+
+use aoc_2023_common::PuzzlePart;
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::{ptr, slice};
+
+/// Outcome of [`aoc_solve`]. New variants are only ever appended, so the values stay stable.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    NullPointer = 1,
+    UnknownDay = 2,
+    UnknownPart = 3,
+    InvalidUtf8 = 4,
+    SolveFailed = 5,
+    Panicked = 6,
+}
+
+/// Solves `part` (1 or 2) of `day` for the `input_len` bytes of UTF-8 at `input`.
+///
+/// On success the answer is stored in `result`; if the solver fails, the error message is stored
+/// there instead. Either way the string is owned by the caller and has to be released with
+/// [`aoc_free_string`]. `result` is set to null if there is nothing to report.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes (or may be null if `input_len` is 0) and
+/// `result` must point to writable storage for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    result: *mut *mut c_char,
+) -> AocStatus {
+    if result.is_null() || (input.is_null() && input_len > 0) {
+        return AocStatus::NullPointer;
+    }
+    *result = ptr::null_mut();
+
+    let Some(day) = aoc_2023_solvers::day(day) else {
+        return AocStatus::UnknownDay;
+    };
+    let puzzle_part = match part {
+        1 => PuzzlePart::One,
+        2 => PuzzlePart::Two,
+        _ => return AocStatus::UnknownPart,
+    };
+    let input = if input_len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, input_len)
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return AocStatus::InvalidUtf8;
+    };
+
+    // unwinding across the C boundary would abort the whole host process
+    let (status, message) =
+        match panic::catch_unwind(AssertUnwindSafe(|| day.solve(input, puzzle_part))) {
+            Ok(Ok(answer)) => (AocStatus::Ok, answer),
+            Ok(Err(err)) => (AocStatus::SolveFailed, format!("{err:#}")),
+            Err(_) => (AocStatus::Panicked, "solver panicked".to_string()),
+        };
+    *result = into_c_string(message);
+
+    status
+}
+
+/// Releases a string returned by [`aoc_solve`]. Does nothing for null.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by [`aoc_solve`] that hasn't been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // error messages may quote the input, which may contain NUL bytes
+    let s = s.replace('\0', "\\0");
+    CString::new(s).expect("NUL bytes were replaced").into_raw()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn solve(day: u8, part: u8, input: &str) -> (AocStatus, Option<String>) {
+        let mut result = ptr::null_mut();
+        let status = unsafe { aoc_solve(day, part, input.as_ptr(), input.len(), &mut result) };
+        let message = (!result.is_null()).then(|| {
+            let message = unsafe { CStr::from_ptr(result) }
+                .to_string_lossy()
+                .into_owned();
+            unsafe { aoc_free_string(result) };
+            message
+        });
+
+        (status, message)
+    }
+
+    #[test]
+    fn test_solve() {
+        let res = solve(6, 2, "Time:      7  15   30\nDistance:  9  40  200");
+        assert_eq!(res, (AocStatus::Ok, Some("71503".to_string())));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(solve(0, 1, ""), (AocStatus::UnknownDay, None));
+        assert_eq!(solve(1, 3, ""), (AocStatus::UnknownPart, None));
+        assert_eq!(
+            solve(5, 1, "garbage"),
+            (
+                AocStatus::SolveFailed,
+                Some("invalid format for seeds string".to_string())
+            )
+        );
+
+        let status = unsafe { aoc_solve(1, 1, ptr::null(), 0, ptr::null_mut()) };
+        assert_eq!(status, AocStatus::NullPointer);
+    }
+
+    /// The header generated by the build script has to match the checked-in one, which is
+    /// updated with `UPDATE_HEADER=1`.
+    #[test]
+    fn test_header_up_to_date() -> std::io::Result<()> {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/aoc_2023.h"));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/aoc_2023.h");
+
+        if std::env::var("UPDATE_HEADER").is_ok_and(|val| val == "1") {
+            return std::fs::write(path, generated);
+        }
+        assert!(
+            std::fs::read_to_string(path)? == generated,
+            "include/aoc_2023.h is out of date, run the tests with UPDATE_HEADER=1 to update it"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! Compiles `harness.c` against the generated header and the built library, then runs it.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_c_harness() -> eyre::Result<()> {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // integration tests live in target/<profile>/deps, right next to the built cdylib
+    let lib_dir = env::current_exe()?
+        .parent()
+        .ok_or_else(|| eyre::eyre!("test binary has no parent directory"))?
+        .to_path_buf();
+    let harness = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("aoc_2023_harness");
+
+    let compiled = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(crate_dir.join("tests/harness.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args(["-Wall", "-Werror", "-laoc_2023_ffi", "-o"])
+        .arg(&harness)
+        .status()?;
+    assert!(compiled.success(), "couldn't compile the C harness");
+
+    let output = Command::new(&harness).output()?;
+    assert!(
+        output.status.success(),
+        "C harness failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
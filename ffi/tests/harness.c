@@ -0,0 +1,46 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc_2023.h"
+
+static int failures = 0;
+
+static void expect(const char *name, enum AocStatus status, enum AocStatus expected_status,
+                   char *result, const char *expected_result) {
+    int ok = status == expected_status &&
+             (expected_result == NULL ? result == NULL
+                                      : result != NULL && strcmp(result, expected_result) == 0);
+    if (!ok) {
+        fprintf(stderr, "%s: expected %d '%s', got %d '%s'\n", name, expected_status,
+                expected_result ? expected_result : "(null)", status, result ? result : "(null)");
+        failures++;
+    }
+    aoc_free_string(result);
+}
+
+int main(void) {
+    const char *races = "Time:      7  15   30\nDistance:  9  40  200";
+    char *result = NULL;
+    enum AocStatus status;
+
+    status = aoc_solve(6, 1, (const uint8_t *)races, strlen(races), &result);
+    expect("day 6 part 1", status, AOC_STATUS_OK, result, "288");
+
+    status = aoc_solve(6, 2, (const uint8_t *)races, strlen(races), &result);
+    expect("day 6 part 2", status, AOC_STATUS_OK, result, "71503");
+
+    status = aoc_solve(42, 1, (const uint8_t *)races, strlen(races), &result);
+    expect("unknown day", status, AOC_STATUS_UNKNOWN_DAY, result, NULL);
+
+    status = aoc_solve(5, 1, (const uint8_t *)"garbage", 7, &result);
+    expect("invalid almanac", status, AOC_STATUS_SOLVE_FAILED, result,
+           "invalid format for seeds string");
+
+    const uint8_t invalid_utf8[] = {0xff, 0xfe};
+    status = aoc_solve(1, 1, invalid_utf8, sizeof(invalid_utf8), &result);
+    expect("invalid utf-8", status, AOC_STATUS_INVALID_UTF8, result, NULL);
+
+    aoc_free_string(NULL);
+
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use aoc_day_5::Almanac;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Almanac::from_str(input);
+    }
+});
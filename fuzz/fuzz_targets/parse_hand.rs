@@ -0,0 +1,12 @@
+#![no_main]
+
+use aoc_2023_common::PuzzlePart;
+use aoc_day_7::Hand;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Hand::try_from((input, PuzzlePart::One));
+        let _ = Hand::try_from((input, PuzzlePart::Two));
+    }
+});
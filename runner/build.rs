@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in the runner's checkout, `None` if that's not possible, e.g. in a source tarball.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+fn main() {
+    // recorded with every run as `AOC_COMMIT`, the runner doesn't know about git at runtime
+    if let Some(commit) = git(&["describe", "--always", "--dirty"]) {
+        println!("cargo:rustc-env=AOC_COMMIT={commit}");
+    }
+
+    // commits move the branch HEAD points to, staging changes the index
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src");
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        println!("cargo:rerun-if-changed={}", git_dir.join("index").display());
+        if let Some(head) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!("cargo:rerun-if-changed={}", git_dir.join(head).display());
+        }
+    }
+}
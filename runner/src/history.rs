@@ -0,0 +1,291 @@
+use aoc_2023_common::input::try_fold_lines;
+use aoc_2023_solvers::Solution;
+
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Write as _};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+
+/// How many of the preceding runs on the same input a run is compared against.
+const BASELINE_RUNS: usize = 5;
+/// How much slower than the median of its baseline a run has to be to count as a regression.
+const REGRESSION_FACTOR: f64 = 1.5;
+/// Slowdowns below this are noise, however large the factor.
+const NOISE_FLOOR_US: u128 = 100;
+
+/// A single run of a solver, as it's stored in the history.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Run {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// `git describe --always --dirty` of the checkout the runner was built from, `None` if it
+    /// wasn't built from a checkout.
+    pub commit: Option<String>,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub input_hash: String,
+    pub total_us: u128,
+    pub spans_us: BTreeMap<String, u128>,
+}
+
+impl Run {
+    pub fn new(solution: &Solution, input: &str) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+            commit: option_env!("AOC_COMMIT").map(str::to_string),
+            day: solution.day,
+            part: solution.part,
+            answer: solution.answer.clone(),
+            input_hash: input_hash(input),
+            total_us: solution.timings.total_us,
+            spans_us: solution
+                .timings
+                .spans_us
+                .iter()
+                .map(|(&name, &us)| (name.to_string(), us))
+                .collect(),
+        }
+    }
+}
+
+/// FNV-1a, which unlike the std hashers is stable across Rust versions.
+fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// The runs recorded so far, one JSON object per line.
+pub struct Store {
+    path: PathBuf,
+}
+
+impl Store {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The store at `HISTORY_FILE`, or `aoc-history.jsonl` in the working directory.
+    pub fn from_env() -> Self {
+        Self::new(env::var("HISTORY_FILE").unwrap_or_else(|_| "aoc-history.jsonl".to_string()))
+    }
+
+    pub fn append(&self, run: &Run) -> eyre::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .wrap_err_with(|| format!("couldn't open history {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(run)?)?;
+
+        Ok(())
+    }
+
+    /// All runs in the order they were recorded. A missing store has no runs.
+    pub fn load(&self) -> eyre::Result<Vec<Run>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err)
+                    .wrap_err_with(|| format!("couldn't open history {}", self.path.display()))
+            }
+        };
+
+        try_fold_lines(BufReader::new(file), vec![], |mut runs, line| {
+            if !line.is_empty() {
+                runs.push(serde_json::from_str(line)?);
+            }
+            Ok(runs)
+        })
+        .wrap_err_with(|| format!("couldn't read history {}", self.path.display()))
+    }
+}
+
+/// Summarizes the runs per day and part, optionally only of a single day: the latest answer and
+/// timing, every change of the answer to an input, and every run that was considerably slower
+/// than the runs on the same input before it.
+pub fn report(runs: &[Run], day: Option<u8>) -> String {
+    let mut by_puzzle = BTreeMap::<(u8, u8), Vec<&Run>>::new();
+    for run in runs
+        .iter()
+        .filter(|run| day.is_none_or(|day| run.day == day))
+    {
+        by_puzzle.entry((run.day, run.part)).or_default().push(run);
+    }
+
+    let mut report = String::new();
+    for ((day, part), runs) in by_puzzle {
+        let latest = runs.last().expect("groups aren't empty");
+        let best = runs
+            .iter()
+            .map(|run| run.total_us)
+            .min()
+            .unwrap_or_default();
+        writeln!(
+            report,
+            "day {day} part {part}: {} runs, latest answer {} in {} (best {})",
+            runs.len(),
+            latest.answer.as_deref().unwrap_or("-"),
+            format_us(latest.total_us),
+            format_us(best),
+        )
+        .expect("writing to a string never fails");
+
+        let mut by_input = HashMap::<&str, Vec<&Run>>::new();
+        for run in runs {
+            let previous = by_input.entry(&run.input_hash).or_default();
+            let at = run.commit.as_deref().unwrap_or("unknown commit");
+
+            if let Some(last) = previous.last() {
+                if last.answer != run.answer {
+                    writeln!(
+                        report,
+                        "  answer changed from {} to {} at {at} (input {})",
+                        last.answer.as_deref().unwrap_or("-"),
+                        run.answer.as_deref().unwrap_or("-"),
+                        run.input_hash,
+                    )
+                    .expect("writing to a string never fails");
+                }
+
+                let baseline = median(
+                    previous
+                        .iter()
+                        .rev()
+                        .take(BASELINE_RUNS)
+                        .map(|run| run.total_us),
+                );
+                if run.total_us as f64 > baseline as f64 * REGRESSION_FACTOR
+                    && run.total_us - baseline >= NOISE_FLOOR_US
+                {
+                    writeln!(
+                        report,
+                        "  regression: {} -> {} ({:.1}x) at {at}",
+                        format_us(baseline),
+                        format_us(run.total_us),
+                        run.total_us as f64 / baseline.max(1) as f64,
+                    )
+                    .expect("writing to a string never fails");
+                }
+            }
+
+            previous.push(run);
+        }
+    }
+
+    if report.is_empty() {
+        report.push_str("no runs recorded yet\n");
+    }
+
+    report
+}
+
+fn median(values: impl Iterator<Item = u128>) -> u128 {
+    let mut values = values.collect::<Vec<_>>();
+    values.sort_unstable();
+
+    values.get(values.len() / 2).copied().unwrap_or_default()
+}
+
+fn format_us(us: u128) -> String {
+    match us {
+        0..1_000 => format!("{us}µs"),
+        1_000..1_000_000 => format!("{:.1}ms", us as f64 / 1e3),
+        _ => format!("{:.2}s", us as f64 / 1e6),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(day: u8, answer: &str, input_hash: &str, total_us: u128) -> Run {
+        Run {
+            timestamp: 0,
+            commit: Some(format!("c{total_us}")),
+            day,
+            part: 1,
+            answer: Some(answer.to_string()),
+            input_hash: input_hash.to_string(),
+            total_us,
+            spans_us: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_store_roundtrip() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = Store::new(dir.path().join("history.jsonl"));
+        assert_eq!(store.load()?, []);
+
+        let runs = [run(1, "142", "a", 10), run(2, "8", "b", 20)];
+        for run in &runs {
+            store.append(run)?;
+        }
+        assert_eq!(store.load()?, runs);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_corrupt_line() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("history.jsonl");
+        let store = Store::new(&path);
+        store.append(&run(1, "142", "a", 10))?;
+        OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(b"{\n")?;
+
+        let err = store.load().unwrap_err();
+        assert_eq!(
+            err.chain().nth(1).map(ToString::to_string).as_deref(),
+            Some("in line 2")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() {
+        let runs = [
+            run(1, "142", "a", 1_000),
+            run(1, "142", "a", 1_100),
+            // a different input may have a different answer
+            run(1, "77", "b", 1_000),
+            run(1, "143", "a", 1_050),
+            run(1, "143", "a", 5_000),
+            run(2, "8", "c", 20),
+        ];
+
+        assert_eq!(
+            report(&runs, Some(1)),
+            "\
+day 1 part 1: 5 runs, latest answer 143 in 5.0ms (best 1.0ms)
+  answer changed from 142 to 143 at c1050 (input a)
+  regression: 1.1ms -> 5.0ms (4.8x) at c5000
+"
+        );
+        assert_eq!(report(&runs, Some(3)), "no runs recorded yet\n");
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+}
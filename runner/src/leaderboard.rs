@@ -0,0 +1,83 @@
+use aoc_2023_common::leaderboard::Leaderboard;
+
+use std::fs;
+
+use eyre::WrapErr;
+
+/// Loads a leaderboard from a file or, for `http(s)://` URLs, from Advent of Code or anything
+/// serving the same JSON. Private leaderboards are only served with the `session` cookie of a
+/// logged in member.
+pub fn load(source: &str, session: Option<&str>) -> eyre::Result<Leaderboard> {
+    let json = if source.starts_with("http://") || source.starts_with("https://") {
+        let mut request = ureq::get(source);
+        if let Some(session) = session {
+            request = request.header("Cookie", format!("session={session}"));
+        }
+
+        request
+            .call()
+            .wrap_err_with(|| format!("couldn't fetch leaderboard {source}"))?
+            .body_mut()
+            .read_to_string()?
+    } else {
+        fs::read_to_string(source)
+            .wrap_err_with(|| format!("couldn't open leaderboard {source}"))?
+    };
+
+    json.parse()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Response, Server};
+
+    const FIXTURE: &str = include_str!("../../common/fixtures/leaderboard.json");
+
+    #[test]
+    fn test_load_from_mock_endpoint() -> eyre::Result<()> {
+        let server = Server::http("127.0.0.1:0").map_err(|err| eyre::eyre!(err))?;
+        let addr = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| eyre::eyre!("not listening on an IP address"))?;
+        let mock = thread::spawn(move || -> eyre::Result<Option<String>> {
+            let request = server.recv()?;
+            let cookie = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Cookie"))
+                .map(|header| header.value.to_string());
+            request.respond(Response::from_string(FIXTURE))?;
+
+            Ok(cookie)
+        });
+
+        let url = format!("http://{addr}/2023/leaderboard/private/view/1001.json");
+        let leaderboard = load(&url, Some("53cr3t"))?;
+        assert_eq!(leaderboard.owner_id, 1001);
+
+        let cookie = mock.join().expect("mock endpoint panicked")?;
+        assert_eq!(cookie.as_deref(), Some("session=53cr3t"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_from_file() -> eyre::Result<()> {
+        let leaderboard = load(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../common/fixtures/leaderboard.json"
+            ),
+            None,
+        )?;
+        assert_eq!(leaderboard.members.len(), 3);
+
+        let err = load("missing.json", None).unwrap_err();
+        assert_eq!(err.to_string(), "couldn't open leaderboard missing.json");
+
+        Ok(())
+    }
+}
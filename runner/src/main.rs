@@ -0,0 +1,153 @@
+use aoc_2023_common::trace::{
+    trace_file_from_env, trace_layer, TraceFormat, TraceGuard, TraceLayer,
+};
+use aoc_2023_common::PuzzlePart;
+use aoc_2023_runner::history::{report, Run, Store};
+use aoc_2023_runner::leaderboard;
+use aoc_2023_solvers::Day;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+
+use eyre::{bail, eyre, WrapErr};
+
+const USAGE: &str = "\
+usage: aoc run [--trace <filter>] [--trace-format human|chrome] [--trace-file <path>]
+               <day> <part> [<input file> | -]
+       aoc svg <day> <part> [<input file> | -]
+       aoc history [<day>]
+       aoc leaderboard <file | url>
+       aoc tui";
+
+fn main() -> eyre::Result<()> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["run", args @ ..] => {
+            let (args, layer, _guard) = tracing_from_args(args)?;
+            match args.as_slice() {
+                [day, part, input @ ..] if input.len() <= 1 => {
+                    run(day, part, input.first().copied(), layer)
+                }
+                _ => bail!("{USAGE}"),
+            }
+        }
+        ["svg", day, part, input @ ..] if input.len() <= 1 => {
+            svg(day, part, input.first().copied())
+        }
+        ["history"] => history(None),
+        ["history", day] => history(Some(day.parse().wrap_err("invalid day")?)),
+        ["leaderboard", source] => leaderboard(source),
+        ["tui"] => aoc_2023_tui::run(),
+        _ => bail!("{USAGE}"),
+    }
+}
+
+/// Builds the tracing layer the `--trace`, `--trace-format` and `--trace-file` flags ask for, like
+/// `TRACE`, `TRACE_FORMAT` and `TRACE_FILE` do for the binaries of the days. Returns the remaining
+/// arguments, the layer and the guard that has to be kept alive until the run is done.
+fn tracing_from_args<'a>(args: &[&'a str]) -> eyre::Result<(Vec<&'a str>, TraceLayer, TraceGuard)> {
+    let mut filter = None;
+    let mut format = None;
+    let mut path = trace_file_from_env();
+    let mut rest = vec![];
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .copied()
+                .ok_or_else(|| eyre!("{arg} needs a value"))
+        };
+        match arg {
+            "--trace" => filter = Some(value()?),
+            "--trace-format" => format = Some(value()?),
+            "--trace-file" => path = value()?.into(),
+            _ => rest.push(arg),
+        }
+    }
+
+    let format = match format {
+        Some(format) => TraceFormat::parse(format, path)?,
+        None => TraceFormat::Human,
+    };
+    let (layer, guard) = trace_layer(filter, format)?;
+    Ok((rest, layer, guard))
+}
+
+/// Looks up the day and part, and reads the input from a file, stdin (`-`), or the bundled puzzle
+/// input if none is given.
+fn puzzle(
+    day: &str,
+    part: &str,
+    input: Option<&str>,
+) -> eyre::Result<(&'static Day, PuzzlePart, String)> {
+    let day = day
+        .parse()
+        .ok()
+        .and_then(aoc_2023_solvers::day)
+        .ok_or_else(|| eyre!("unknown day: {day}"))?;
+    let puzzle_part = part.parse::<PuzzlePart>()?;
+    let input = match input {
+        None => day.puzzle_input.to_string(),
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+        Some(path) => {
+            fs::read_to_string(path).wrap_err_with(|| format!("couldn't open input {path}"))?
+        }
+    };
+
+    Ok((day, puzzle_part, input))
+}
+
+/// Solves a puzzle and records the run in the history.
+fn run(day: &str, part: &str, input: Option<&str>, layer: TraceLayer) -> eyre::Result<()> {
+    let (day, puzzle_part, input) = puzzle(day, part, input)?;
+    let solution = day.solve_traced(&input, puzzle_part, layer);
+    Store::from_env().append(&Run::new(&solution, &input))?;
+
+    let Some(answer) = solution.answer else {
+        bail!("{}", solution.diagnostics.join(": "));
+    };
+    println!(
+        "The result for day {} puzzle part '{puzzle_part:?}' is: {answer} ({}µs)",
+        day.day, solution.timings.total_us
+    );
+
+    Ok(())
+}
+
+/// Prints a picture of the solution.
+fn svg(day: &str, part: &str, input: Option<&str>) -> eyre::Result<()> {
+    let (day, puzzle_part, input) = puzzle(day, part, input)?;
+    let render_svg = day
+        .render_svg
+        .ok_or_else(|| eyre!("day {} has no pictures", day.day))?;
+    print!("{}", render_svg(&input, puzzle_part)?);
+
+    Ok(())
+}
+
+fn history(day: Option<u8>) -> eyre::Result<()> {
+    print!("{}", report(&Store::from_env().load()?, day));
+
+    Ok(())
+}
+
+/// Shows the standings of a private leaderboard. Fetching one needs the session cookie of a
+/// member in `AOC_SESSION`.
+fn leaderboard(source: &str) -> eyre::Result<()> {
+    let session = env::var("AOC_SESSION").ok();
+    let leaderboard = leaderboard::load(source, session.as_deref())?;
+
+    print!("{}", leaderboard.render_standings());
+    println!();
+    print!("{}", leaderboard.render_deltas());
+
+    Ok(())
+}
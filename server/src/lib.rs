@@ -0,0 +1,183 @@
+use aoc_2023_common::PuzzlePart;
+use aoc_2023_solvers::DAYS;
+
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DayListing {
+    pub day: u8,
+    pub title: &'static str,
+    pub parts: [u8; 2],
+}
+
+/// Answers requests until the server shuts down.
+pub fn serve(server: &Server) {
+    for request in server.incoming_requests() {
+        if let Err(err) = handle(request) {
+            eprintln!("couldn't respond to request: {err}");
+        }
+    }
+}
+
+fn handle(mut request: Request) -> std::io::Result<()> {
+    let mut body = Vec::new();
+    request.as_reader().read_to_end(&mut body)?;
+
+    let (status, json) = route(request.method(), request.url(), &body);
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("header is valid");
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+
+    request.respond(response)
+}
+
+/// Maps a request to the status code and JSON body of its response.
+pub fn route(method: &Method, url: &str, body: &[u8]) -> (u16, serde_json::Value) {
+    let segments = url
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["days"]) => (200, json!(days())),
+        (Method::Post, ["solve", day, part]) => {
+            let Some(day) = day.parse().ok().and_then(aoc_2023_solvers::day) else {
+                return error(404, format!("unknown day: {day}"));
+            };
+            let Ok(puzzle_part) = part.parse::<PuzzlePart>() else {
+                return error(404, format!("unknown puzzle part: {part}"));
+            };
+            let Ok(input) = std::str::from_utf8(body) else {
+                return error(400, "input is not valid UTF-8".to_string());
+            };
+
+            let solution = day.solve_timed(input, puzzle_part);
+            let status = if solution.answer.is_some() { 200 } else { 422 };
+            (status, json!(solution))
+        }
+        (_, ["days"] | ["solve", _, _]) => error(405, format!("method not allowed: {method}")),
+        _ => error(404, format!("not found: {url}")),
+    }
+}
+
+fn error(status: u16, message: String) -> (u16, serde_json::Value) {
+    (status, json!({ "error": message }))
+}
+
+pub fn days() -> Vec<DayListing> {
+    DAYS.iter()
+        .map(|day| DayListing {
+            day: day.day,
+            title: day.title,
+            parts: [1, 2],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+
+    const DAY_6_EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    /// Sends a raw HTTP/1.1 request and returns the status code and JSON body of the response.
+    fn request(
+        addr: std::net::SocketAddr,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> eyre::Result<(u16, serde_json::Value)> {
+        let mut stream = TcpStream::connect(addr)?;
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| eyre::eyre!("malformed response: {response}"))?;
+        let status = head
+            .split(' ')
+            .nth(1)
+            .ok_or_else(|| eyre::eyre!("missing status: {head}"))?
+            .parse()?;
+
+        Ok((status, serde_json::from_str(body)?))
+    }
+
+    #[test]
+    fn test_route_days() {
+        let (status, json) = route(&Method::Get, "/days", b"");
+        assert_eq!(status, 200);
+        assert_eq!(json.as_array().map(Vec::len), Some(9));
+        assert_eq!(
+            json[4],
+            json!({ "day": 5, "title": "If You Give A Seed A Fertilizer", "parts": [1, 2] })
+        );
+    }
+
+    #[test]
+    fn test_route_solve() {
+        let (status, json) = route(&Method::Post, "/solve/6/2", DAY_6_EXAMPLE.as_bytes());
+        assert_eq!(status, 200);
+        assert_eq!(json["answer"], "71503");
+        assert_eq!(json["diagnostics"], json!([]));
+        assert!(json["timings"]["spans_us"]["solve"].is_u64());
+        assert!(json["timings"]["spans_us"]["parse_input_part_2"].is_u64());
+    }
+
+    #[test]
+    fn test_route_solve_diagnostics() {
+        let (status, json) = route(&Method::Post, "/solve/5/one", b"garbage");
+        assert_eq!(status, 422);
+        assert_eq!(json["answer"], serde_json::Value::Null);
+        assert_eq!(
+            json["diagnostics"],
+            json!(["invalid format for seeds string"])
+        );
+    }
+
+    #[test]
+    fn test_route_errors() {
+        assert_eq!(route(&Method::Post, "/solve/26/1", b"").0, 404);
+        assert_eq!(route(&Method::Post, "/solve/1/3", b"").0, 404);
+        assert_eq!(route(&Method::Post, "/solve/1/1", &[0xff]).0, 400);
+        assert_eq!(route(&Method::Get, "/solve/1/1", b"").0, 405);
+        assert_eq!(route(&Method::Get, "/", b"").0, 404);
+    }
+
+    #[test]
+    fn test_serve_on_localhost() -> eyre::Result<()> {
+        let server = Server::http("127.0.0.1:0").map_err(|err| eyre::eyre!(err))?;
+        let addr = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| eyre::eyre!("not listening on an IP address"))?;
+        thread::spawn(move || serve(&server));
+
+        let (status, json) = request(addr, "GET", "/days", "")?;
+        assert_eq!(status, 200);
+        assert_eq!(json[0]["title"], "Trebuchet?!");
+
+        let (status, json) = request(addr, "POST", "/solve/6/1", DAY_6_EXAMPLE)?;
+        assert_eq!(status, 200);
+        assert_eq!(json["answer"], "288");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use aoc_2023_server::serve;
+
+use std::env;
+
+use eyre::eyre;
+use tiny_http::Server;
+
+fn main() -> eyre::Result<()> {
+    let addr = env::var("SERVER_ADDR").unwrap_or_else(|_| "127.0.0.1:2023".to_string());
+    let server = Server::http(&addr).map_err(|err| eyre!(err))?;
+    println!("Listening on http://{}", server.server_addr());
+
+    serve(&server);
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use aoc_2023_common::explain::Explain;
+use aoc_2023_common::minimize::catch_panic;
+use aoc_2023_common::svg::Render;
+use aoc_2023_common::PuzzlePart;
+
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use serde::Serialize;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Identity, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+use crate::timings::SpanTimings;
+
+mod timings;
+
+/// A puzzle day whose answers can be computed without knowing the concrete types of its solver.
+pub struct Day {
+    pub day: u8,
+    pub title: &'static str,
+    /// The bundled puzzle input.
+    pub puzzle_input: &'static str,
+    /// Step-by-step explanation of the solution, for the days that have one.
+    pub explain: Option<Explain>,
+    /// Pictures of the solution, for the days that can draw one.
+    pub render_svg: Option<Render>,
+    solve: fn(&str, PuzzlePart) -> eyre::Result<String>,
+}
+
+impl Day {
+    pub fn solve(&self, input: &str, puzzle_part: PuzzlePart) -> eyre::Result<String> {
+        (self.solve)(input, puzzle_part)
+    }
+
+    /// Solves `input`, timing the solver's spans. Errors and panics end up in the diagnostics.
+    pub fn solve_timed(&self, input: &str, puzzle_part: PuzzlePart) -> Solution {
+        self.solve_traced(input, puzzle_part, Identity::new())
+    }
+
+    /// Same as [`Day::solve_timed`], but the solver's spans and events are also passed to `layer`,
+    /// as the subscriber timing them replaces the global one while solving.
+    pub fn solve_traced(
+        &self,
+        input: &str,
+        puzzle_part: PuzzlePart,
+        layer: impl Layer<Registry> + Send + Sync,
+    ) -> Solution {
+        let span_timings = SpanTimings::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(layer)
+            .with(span_timings.clone().with_filter(LevelFilter::DEBUG));
+
+        let start = Instant::now();
+        let res = tracing::subscriber::with_default(subscriber, || {
+            catch_panic(|| self.solve(input, puzzle_part))
+        });
+        let total = start.elapsed();
+
+        let (answer, diagnostics) = match res {
+            Ok(Ok(answer)) => (Some(answer), vec![]),
+            Ok(Err(err)) => (None, err.chain().map(ToString::to_string).collect()),
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(ToString::to_string)
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                (None, vec![format!("solver panicked: {message}")])
+            }
+        };
+
+        Solution {
+            day: self.day,
+            part: match puzzle_part {
+                PuzzlePart::One => 1,
+                PuzzlePart::Two => 2,
+            },
+            answer,
+            timings: Timings {
+                total_us: total.as_micros(),
+                spans_us: span_timings
+                    .busy()
+                    .into_iter()
+                    .map(|(name, busy)| (name, busy.as_micros()))
+                    .collect(),
+            },
+            diagnostics,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    /// Rendered as a string, as some answers don't fit into the integers of every JSON parser.
+    pub answer: Option<String>,
+    pub timings: Timings,
+    /// The error chain if the input couldn't be solved, outermost error first.
+    pub diagnostics: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Timings {
+    pub total_us: u128,
+    /// Time spent inside the solver's spans (parsing, solving, ...), keyed by span name.
+    pub spans_us: BTreeMap<&'static str, u128>,
+}
+
+pub static DAYS: [Day; 9] = [
+    Day {
+        day: 1,
+        title: "Trebuchet?!",
+        puzzle_input: include_str!("../../day-1/src/input.txt"),
+        explain: Some(aoc_day_1::explain),
+        render_svg: None,
+        solve: |input, puzzle_part| Ok(aoc_day_1::calculate_result(input, puzzle_part).to_string()),
+    },
+    Day {
+        day: 2,
+        title: "Cube Conundrum",
+        puzzle_input: include_str!("../../day-2/src/input.txt"),
+        explain: None,
+        render_svg: None,
+        solve: |input, puzzle_part| {
+            aoc_day_2::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 3,
+        title: "Gear Ratios",
+        puzzle_input: include_str!("../../day-3/src/input.txt"),
+        explain: Some(aoc_day_3::explain),
+        render_svg: Some(aoc_day_3::render_svg),
+        solve: |input, puzzle_part| {
+            aoc_day_3::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 4,
+        title: "Scratchcards",
+        puzzle_input: include_str!("../../day-4/src/input.txt"),
+        explain: None,
+        render_svg: None,
+        solve: |input, puzzle_part| {
+            aoc_day_4::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 5,
+        title: "If You Give A Seed A Fertilizer",
+        puzzle_input: include_str!("../../day-5/src/input.txt"),
+        explain: Some(aoc_day_5::explain),
+        render_svg: Some(aoc_day_5::render_svg),
+        solve: |input, puzzle_part| {
+            aoc_day_5::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 6,
+        title: "Wait For It",
+        puzzle_input: include_str!("../../day-6/src/input.txt"),
+        explain: None,
+        render_svg: Some(aoc_day_6::render_svg),
+        solve: |input, puzzle_part| {
+            aoc_day_6::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 7,
+        title: "Camel Cards",
+        puzzle_input: include_str!("../../day-7/src/input.txt"),
+        explain: None,
+        render_svg: None,
+        solve: |input, puzzle_part| {
+            aoc_day_7::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 8,
+        title: "Haunted Wasteland",
+        puzzle_input: include_str!("../../day-8/src/input.txt"),
+        explain: Some(aoc_day_8::explain),
+        render_svg: Some(aoc_day_8::render_svg),
+        solve: |input, puzzle_part| {
+            aoc_day_8::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+    Day {
+        day: 9,
+        title: "Mirage Maintenance",
+        puzzle_input: include_str!("../../day-9/src/input.txt"),
+        explain: Some(aoc_day_9::explain),
+        render_svg: Some(aoc_day_9::render_svg),
+        solve: |input, puzzle_part| {
+            aoc_day_9::calculate_result(input, puzzle_part).map(|res| res.to_string())
+        },
+    },
+];
+
+pub fn day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve() -> eyre::Result<()> {
+        let day = day(6).expect("day 6 exists");
+        let res = day.solve(
+            "Time:      7  15   30\nDistance:  9  40  200",
+            PuzzlePart::Two,
+        )?;
+        assert_eq!(res, "71503");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> eyre::Result<()> {
+        let explain = day(9)
+            .and_then(|day| day.explain)
+            .expect("day 9 can be explained");
+        let steps = explain("0 3 6 9 12 15", PuzzlePart::One)?;
+        assert_eq!(steps.len(), 1);
+
+        assert!(day(2).is_some_and(|day| day.explain.is_none()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_timed() {
+        let day = day(6).expect("day 6 exists");
+        let solution = day.solve_timed("Time: 7\nDistance: 9", PuzzlePart::One);
+        assert_eq!(solution.answer.as_deref(), Some("4"));
+        assert!(solution.timings.spans_us.contains_key("solve"));
+
+        let solution = day.solve_timed("garbage", PuzzlePart::One);
+        assert_eq!(solution.answer, None);
+        assert!(!solution.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(day(0).is_none());
+        assert!(day(26).is_none());
+    }
+}
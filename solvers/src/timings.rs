@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::span::Id;
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Sums up how long the solvers spent inside each of their spans, keyed by span name.
+#[derive(Clone, Default)]
+pub struct SpanTimings {
+    busy: Arc<Mutex<BTreeMap<&'static str, Duration>>>,
+}
+
+struct Entered(Instant);
+
+impl SpanTimings {
+    pub fn busy(&self) -> BTreeMap<&'static str, Duration> {
+        self.busy.lock().expect("span timings poisoned").clone()
+    }
+}
+
+impl<S> Layer<S> for SpanTimings
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Entered(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(Entered(entered)) = span.extensions_mut().remove::<Entered>() else {
+            return;
+        };
+
+        *self
+            .busy
+            .lock()
+            .expect("span timings poisoned")
+            .entry(span.name())
+            .or_default() += entered.elapsed();
+    }
+}
@@ -0,0 +1,260 @@
+use aoc_2023_common::explain::Step;
+use aoc_2023_common::PuzzlePart;
+use aoc_2023_solvers::{Day, DAYS};
+
+use std::fs;
+
+use eyre::WrapErr;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Steps skipped by page up and page down.
+const PAGE_STEPS: usize = 10;
+
+pub struct App {
+    pub screen: Screen,
+    pub quit: bool,
+}
+
+pub enum Screen {
+    Days { selected: usize },
+    Explorer(Explorer),
+}
+
+/// A day being looked at with some input.
+pub struct Explorer {
+    pub day: &'static Day,
+    pub puzzle_part: PuzzlePart,
+    /// Where the input came from, e.g. a path.
+    pub source: String,
+    pub input: String,
+    pub content: Content,
+    pub step: usize,
+    /// Lines scrolled down within the current step.
+    pub scroll: u16,
+    /// The path being typed after pressing `o`.
+    pub prompt: Option<String>,
+    /// Shown until the next key press, e.g. why an input couldn't be loaded.
+    pub status: Option<String>,
+}
+
+pub enum Content {
+    Steps(Vec<Step>),
+    /// For days without an explanation.
+    Answer(String),
+    /// The error chain, outermost error first.
+    Error(Vec<String>),
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            screen: Screen::Days { selected: 0 },
+            quit: false,
+        }
+    }
+}
+
+impl App {
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+
+        match &mut self.screen {
+            Screen::Days { selected } => match key.code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(DAYS.len() - 1),
+                KeyCode::Enter => {
+                    let day = &DAYS[*selected];
+                    self.screen = Screen::Explorer(Explorer::new(
+                        day,
+                        PuzzlePart::One,
+                        "puzzle input".to_string(),
+                        day.puzzle_input.to_string(),
+                    ));
+                }
+                KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+                _ => {}
+            },
+            Screen::Explorer(explorer) if explorer.prompt.is_some() => {
+                explorer.handle_prompt_key(key)
+            }
+            Screen::Explorer(explorer) => match key.code {
+                KeyCode::Esc => {
+                    let selected = DAYS
+                        .iter()
+                        .position(|day| day.day == explorer.day.day)
+                        .unwrap_or_default();
+                    self.screen = Screen::Days { selected };
+                }
+                KeyCode::Char('q') => self.quit = true,
+                _ => explorer.handle_key(key),
+            },
+        }
+    }
+}
+
+impl Explorer {
+    pub fn new(day: &'static Day, puzzle_part: PuzzlePart, source: String, input: String) -> Self {
+        let mut explorer = Self {
+            day,
+            puzzle_part,
+            source,
+            input,
+            content: Content::Steps(vec![]),
+            step: 0,
+            scroll: 0,
+            prompt: None,
+            status: None,
+        };
+        explorer.recompute();
+        explorer
+    }
+
+    pub fn step_count(&self) -> usize {
+        match &self.content {
+            Content::Steps(steps) => steps.len(),
+            Content::Answer(_) | Content::Error(_) => 0,
+        }
+    }
+
+    fn recompute(&mut self) {
+        self.content = match self.day.explain {
+            Some(explain) => explain(&self.input, self.puzzle_part).map(Content::Steps),
+            None => self
+                .day
+                .solve(&self.input, self.puzzle_part)
+                .map(Content::Answer),
+        }
+        .unwrap_or_else(|err| Content::Error(err.chain().map(ToString::to_string).collect()));
+        self.step = 0;
+        self.scroll = 0;
+    }
+
+    fn go_to(&mut self, step: usize) {
+        self.step = step.min(self.step_count().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.status = None;
+
+        match key.code {
+            KeyCode::Right | KeyCode::Char('l') => self.go_to(self.step + 1),
+            KeyCode::Left | KeyCode::Char('h') => self.go_to(self.step.saturating_sub(1)),
+            KeyCode::PageDown => self.go_to(self.step + PAGE_STEPS),
+            KeyCode::PageUp => self.go_to(self.step.saturating_sub(PAGE_STEPS)),
+            KeyCode::Home => self.go_to(0),
+            KeyCode::End => self.go_to(usize::MAX),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('1') if self.puzzle_part != PuzzlePart::One => {
+                self.puzzle_part = PuzzlePart::One;
+                self.recompute();
+            }
+            KeyCode::Char('2') if self.puzzle_part != PuzzlePart::Two => {
+                self.puzzle_part = PuzzlePart::Two;
+                self.recompute();
+            }
+            KeyCode::Char('o') => self.prompt = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) {
+        let Some(path) = &mut self.prompt else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char(c) => path.push(c),
+            KeyCode::Backspace => {
+                path.pop();
+            }
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Enter => {
+                let path = self.prompt.take().unwrap_or_default();
+                match fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("couldn't open input {path}"))
+                {
+                    Ok(input) => {
+                        self.source = path;
+                        self.input = input;
+                        self.recompute();
+                    }
+                    Err(err) => self.status = Some(format!("{err:#}")),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn press(app: &mut App, codes: impl IntoIterator<Item = KeyCode>) {
+        for code in codes {
+            app.handle_key(KeyEvent::from(code));
+        }
+    }
+
+    fn exploring(app: &App) -> &Explorer {
+        match &app.screen {
+            Screen::Explorer(explorer) => explorer,
+            Screen::Days { .. } => panic!("not exploring a day"),
+        }
+    }
+
+    #[test]
+    fn test_step_through_day() {
+        let mut app = App::default();
+        press(&mut app, [KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+
+        let explorer = exploring(&app);
+        assert_eq!(explorer.day.day, 3);
+        assert!(explorer.step_count() > PAGE_STEPS);
+
+        press(&mut app, [KeyCode::Right, KeyCode::Right, KeyCode::Left]);
+        assert_eq!(exploring(&app).step, 1);
+        press(&mut app, [KeyCode::PageDown]);
+        assert_eq!(exploring(&app).step, 1 + PAGE_STEPS);
+        press(&mut app, [KeyCode::End, KeyCode::Right]);
+        assert_eq!(exploring(&app).step, exploring(&app).step_count() - 1);
+
+        press(&mut app, [KeyCode::Char('2')]);
+        assert_eq!(exploring(&app).puzzle_part, PuzzlePart::Two);
+        assert_eq!(exploring(&app).step, 0);
+
+        press(&mut app, [KeyCode::Esc]);
+        assert!(matches!(app.screen, Screen::Days { selected: 2 }));
+        press(&mut app, [KeyCode::Char('q')]);
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_day_without_explanation() {
+        let mut app = App::default();
+        press(&mut app, [KeyCode::Down, KeyCode::Enter]);
+
+        assert_eq!(exploring(&app).day.day, 2);
+        assert!(matches!(exploring(&app).content, Content::Answer(_)));
+    }
+
+    #[test]
+    fn test_open_missing_input() {
+        let mut app = App::default();
+        press(&mut app, [KeyCode::Enter, KeyCode::Char('o')]);
+        press(&mut app, "missing.txt".chars().map(KeyCode::Char));
+        press(&mut app, [KeyCode::Enter]);
+
+        let explorer = exploring(&app);
+        assert_eq!(explorer.source, "puzzle input");
+        assert!(explorer
+            .status
+            .as_deref()
+            .is_some_and(|status| status.starts_with("couldn't open input missing.txt")));
+    }
+}
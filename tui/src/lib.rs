@@ -0,0 +1,29 @@
+use crate::app::App;
+use crate::ui::render;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+pub mod app;
+pub mod ui;
+
+/// Runs the TUI until it is quit, restoring the terminal afterwards.
+pub fn run() -> eyre::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut app = App::default();
+
+    let res = (|| -> eyre::Result<()> {
+        while !app.quit {
+            terminal.draw(|frame| render(&app, frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+
+        Ok(())
+    })();
+    ratatui::restore();
+
+    res
+}
@@ -0,0 +1,3 @@
+fn main() -> eyre::Result<()> {
+    aoc_2023_tui::run()
+}
@@ -0,0 +1,174 @@
+use aoc_2023_common::explain::{Highlight, Span as ExplainSpan};
+use aoc_2023_common::PuzzlePart;
+use aoc_2023_solvers::DAYS;
+
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::{App, Content, Explorer, Screen};
+
+pub fn render(app: &App, frame: &mut Frame) {
+    match &app.screen {
+        Screen::Days { selected } => render_days(*selected, frame),
+        Screen::Explorer(explorer) => render_explorer(explorer, frame),
+    }
+}
+
+fn render_days(selected: usize, frame: &mut Frame) {
+    let [list_area, help_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+
+    let items = DAYS.iter().map(|day| {
+        let mut line = Line::from(format!("Day {}: {}", day.day, day.title));
+        if day.explain.is_none() {
+            line.push_span(Span::from("  (answer only)").dark_gray());
+        }
+        ListItem::new(line)
+    });
+    let list = List::new(items)
+        .block(Block::bordered().title(" Advent of Code 2023 "))
+        .highlight_symbol("> ")
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(
+        list,
+        list_area,
+        &mut ListState::default().with_selected(Some(selected)),
+    );
+
+    frame.render_widget(
+        Line::from("↑/↓ select · enter explore · q quit").dark_gray(),
+        help_area,
+    );
+}
+
+fn render_explorer(explorer: &Explorer, frame: &mut Frame) {
+    let [caption_area, body_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let part = match explorer.puzzle_part {
+        PuzzlePart::One => "one",
+        PuzzlePart::Two => "two",
+    };
+    let title = format!(
+        " Day {}: {} · part {part} · {} ",
+        explorer.day.day, explorer.day.title, explorer.source
+    );
+
+    let (caption, body) = match &explorer.content {
+        Content::Steps(steps) => match steps.get(explorer.step) {
+            Some(step) => (
+                format!("{}/{}: {}", explorer.step + 1, steps.len(), step.caption),
+                Text::from_iter(
+                    step.lines
+                        .iter()
+                        .map(|spans| Line::from_iter(spans.iter().map(span))),
+                ),
+            ),
+            None => ("nothing to explain".to_string(), Text::default()),
+        },
+        Content::Answer(answer) => (
+            format!("The answer is {answer}"),
+            Text::from("There's no step by step explanation for this day.").dark_gray(),
+        ),
+        Content::Error(chain) => (
+            "The input couldn't be solved".to_string(),
+            Text::from_iter(chain.iter().map(String::as_str)).red(),
+        ),
+    };
+
+    frame.render_widget(
+        Paragraph::new(caption)
+            .bold()
+            .block(Block::bordered().title(title)),
+        caption_area,
+    );
+    frame.render_widget(
+        Paragraph::new(body)
+            .block(Block::bordered())
+            .scroll((explorer.scroll, 0)),
+        body_area,
+    );
+
+    let help = match (&explorer.prompt, &explorer.status) {
+        (Some(path), _) => Line::from(format!("open: {path}█")),
+        (None, Some(status)) => Line::from(status.as_str()).red(),
+        (None, None) => Line::from(
+            "←/→ step · pgup/pgdn ±10 · home/end · ↑/↓ scroll · 1/2 part · o open · esc back",
+        )
+        .dark_gray(),
+    };
+    frame.render_widget(Paragraph::new(help).wrap(Wrap { trim: true }), help_area);
+}
+
+fn span(span: &ExplainSpan) -> Span<'_> {
+    let style = match span.highlight {
+        Highlight::None => Style::new(),
+        Highlight::Focus => Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        Highlight::Match => Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+        Highlight::Dim => Style::new().fg(Color::DarkGray),
+    };
+
+    Span::styled(span.text.as_str(), style)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_2023_solvers::day;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn draw(app: &App) -> eyre::Result<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12))?;
+        terminal.draw(|frame| render(app, frame))?;
+
+        let buffer = terminal.backend().buffer();
+        let rows = buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>();
+        Ok(rows.join("\n"))
+    }
+
+    #[test]
+    fn test_render_days() -> eyre::Result<()> {
+        let screen = draw(&App::default())?;
+
+        assert!(screen.contains("> Day 1: Trebuchet?!"));
+        assert!(!screen.contains("Day 1: Trebuchet?!  (answer only)"));
+        assert!(screen.contains("  Day 2: Cube Conundrum  (answer only)"));
+        assert!(screen.contains("  Day 9: Mirage Maintenance"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_explorer() -> eyre::Result<()> {
+        let day = day(9).expect("day 9 exists");
+        let explorer = Explorer::new(
+            day,
+            PuzzlePart::One,
+            "example".to_string(),
+            "0 3 6 9 12 15\n1 3 6 10 15 21".to_string(),
+        );
+        let app = App {
+            screen: Screen::Explorer(explorer),
+            quit: false,
+        };
+        let screen = draw(&app)?;
+
+        assert!(screen.contains("Day 9: Mirage Maintenance · part one · example"));
+        assert!(screen.contains("1/2: history 1: next value 18, the sum is now 18"));
+        assert!(screen.contains("   0   3   6   9  12  15  18"));
+
+        Ok(())
+    }
+}